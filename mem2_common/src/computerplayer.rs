@@ -0,0 +1,190 @@
+//! The computer opponent plays as player 2 in the same browser.
+//! It remembers the cards it has seen flipped, but not all of them:
+//! the memory strength is the probability to remember a card and the count of cards it can remember.
+//! With a known pair it clicks the pair, else it flips a card it does not know.
+//! It uses the same `GameEngine` rules as a human player.
+
+//region: use statements
+use crate::gameengine::GameEngine;
+use crate::CardStatusCardFace;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+//endregion
+
+//region: enum, structs, const,...
+///how good is the memory of the computer
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum MemoryStrength {
+    ///remembers half of the cards and only the last 4
+    Easy,
+    ///remembers most of the cards and the last 10
+    Medium,
+    ///remembers every card
+    Perfect,
+}
+
+///a card the computer has seen
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+struct RememberedCard {
+    ///card index in the grid
+    card_index: usize,
+    ///the card number that was revealed
+    card_number: usize,
+}
+
+///the computer opponent and its memory
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ComputerPlayer {
+    ///how good is the memory
+    pub memory_strength: MemoryStrength,
+    ///the cards it remembers, the oldest first
+    vec_remembered_cards: Vec<RememberedCard>,
+}
+//endregion
+
+impl MemoryStrength {
+    ///the probability to remember a card that was flipped
+    pub fn recall_probability(self) -> f64 {
+        match self {
+            MemoryStrength::Easy => 0.5,
+            MemoryStrength::Medium => 0.8,
+            MemoryStrength::Perfect => 1.0,
+        }
+    }
+    ///how many cards it can remember. The oldest are forgotten first.
+    pub fn capacity(self) -> usize {
+        match self {
+            MemoryStrength::Easy => 4,
+            MemoryStrength::Medium => 10,
+            MemoryStrength::Perfect => usize::MAX,
+        }
+    }
+    ///the next strength for the picker
+    pub fn next(self) -> Self {
+        match self {
+            MemoryStrength::Easy => MemoryStrength::Medium,
+            MemoryStrength::Medium => MemoryStrength::Perfect,
+            MemoryStrength::Perfect => MemoryStrength::Easy,
+        }
+    }
+    ///the name for the picker
+    pub fn name(self) -> &'static str {
+        match self {
+            MemoryStrength::Easy => "easy",
+            MemoryStrength::Medium => "medium",
+            MemoryStrength::Perfect => "perfect",
+        }
+    }
+}
+
+impl ComputerPlayer {
+    ///the computer with an empty memory
+    pub fn new(memory_strength: MemoryStrength) -> Self {
+        ComputerPlayer {
+            memory_strength,
+            vec_remembered_cards: Vec::new(),
+        }
+    }
+    ///the computer sees a flipped card and maybe remembers it
+    pub fn observe(&mut self, game_engine: &GameEngine, card_index: usize, rng: &mut impl Rng) {
+        let card_number = match game_engine.vec_cards.get(card_index) {
+            Some(card) if card.card_number_and_img_src != 0 => card.card_number_and_img_src,
+            _ => return,
+        };
+        if !rng.gen_bool(self.memory_strength.recall_probability()) {
+            return;
+        }
+        self.vec_remembered_cards
+            .retain(|remembered_card| remembered_card.card_index != card_index);
+        self.vec_remembered_cards.push(RememberedCard {
+            card_index,
+            card_number,
+        });
+        while self.vec_remembered_cards.len() > self.memory_strength.capacity() {
+            self.vec_remembered_cards.remove(0);
+        }
+    }
+    ///count of cards it remembers
+    pub fn remembered_count(&self) -> usize {
+        self.vec_remembered_cards.len()
+    }
+    ///the card to click now. None if it is not the time to click.
+    pub fn choose_click(&self, game_engine: &GameEngine, rng: &mut impl Rng) -> Option<usize> {
+        //the remembered cards that are still face down
+        let vec_known: Vec<RememberedCard> = self
+            .vec_remembered_cards
+            .iter()
+            .filter(|remembered_card| is_face_down(game_engine, remembered_card.card_index))
+            .copied()
+            .collect();
+        match game_engine.count_click_inside_one_turn {
+            0 => {
+                //a known pair first
+                for (position, first) in vec_known.iter().enumerate() {
+                    if vec_known
+                        .iter()
+                        .skip(position + 1)
+                        .any(|second| game_engine.is_pair(first.card_number, second.card_number))
+                    {
+                        return Some(first.card_index);
+                    }
+                }
+                random_unknown_card(game_engine, &vec_known, rng)
+            }
+            1 => {
+                let first_card_number = game_engine
+                    .vec_cards
+                    .get(game_engine.card_index_of_first_click)?
+                    .card_number_and_img_src;
+                //the other face of the pair if it is known
+                vec_known
+                    .iter()
+                    .find(|remembered_card| {
+                        game_engine.is_pair(first_card_number, remembered_card.card_number)
+                    })
+                    .map(|remembered_card| remembered_card.card_index)
+                    .or_else(|| random_unknown_card(game_engine, &vec_known, rng))
+            }
+            _ => None,
+        }
+    }
+}
+
+///the card is in the grid and face down
+fn is_face_down(game_engine: &GameEngine, card_index: usize) -> bool {
+    card_index != 0
+        && matches!(
+            game_engine.vec_cards.get(card_index),
+            Some(card) if card.status == CardStatusCardFace::Down
+        )
+}
+
+///a random face down card that is not known. If all are known, any face down card.
+fn random_unknown_card(
+    game_engine: &GameEngine,
+    vec_known: &[RememberedCard],
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    let vec_face_down: Vec<usize> = (1..game_engine.vec_cards.len())
+        .filter(|card_index| is_face_down(game_engine, *card_index))
+        .collect();
+    let vec_unknown: Vec<usize> = vec_face_down
+        .iter()
+        .filter(|card_index| {
+            !vec_known
+                .iter()
+                .any(|remembered_card| remembered_card.card_index == **card_index)
+        })
+        .copied()
+        .collect();
+    let vec_candidates = if vec_unknown.is_empty() {
+        vec_face_down
+    } else {
+        vec_unknown
+    };
+    if vec_candidates.is_empty() {
+        None
+    } else {
+        Some(vec_candidates[rng.gen_range(0, vec_candidates.len())])
+    }
+}
@@ -0,0 +1,838 @@
+//! The rules of the memory game without any DOM, WebSocket or sound.
+//! The `GameEngine` owns the cards, the turn, the click counters and the points.
+//! An action is applied to the engine and it returns the events that happened.
+//! The rendering and the networking only react to that events.
+//! So the rules can run and be tested everywhere, not only in the browser.
+//! The deck owner (the player that builds the deck) publishes only the commitments of the cards.
+//! A card value is revealed with its salt only when the card is flipped.
+//! The other player verifies the revealed value against the commitment before using it.
+//! Both players compare the hash of the public game state after every move.
+//! On mismatch the deck owner sends a snapshot of the public game state that replaces the other one.
+//! The moves of the other player are validated before they are applied.
+//! An illegal move is a `RuleViolation` and changes nothing.
+//! A pair is 2 cards with the same card number, or the 2 faces of a `CardPair` from the content pack.
+//! When all pairs are found the player with the most points wins, or more players share a draw.
+//! The rematch starts with the player after the one that started the game before.
+
+//region: use statements
+use crate::{Card, CardPair, CardReveal, CardStatusCardFace, GameSnapshot, GridSize};
+use rand::rngs::{OsRng, SmallRng};
+use rand::FromEntropy;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Write;
+//endregion
+
+//region: enum, structs, const,...
+///everything needed to build exactly the same deck of cards again.
+///It is enough to reproduce a game for bug reports and tests.
+///Only the deck owner knows it, because the seed reveals all the cards.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct DeckParameters {
+    ///seed for the random generator that chooses and shuffles the cards
+    pub seed: u64,
+    ///rows and columns of the card grid
+    pub grid_size: GridSize,
+    ///count of different pairs in the content pack, without the card face down
+    pub card_images_count: usize,
+}
+
+///the actions a player can do
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameAction {
+    ///click on a card face down to flip it over
+    Click {
+        ///card index
+        card_index: usize,
+    },
+    ///the deck owner revealed the value of a card
+    Reveal {
+        ///card index, card number and salt
+        card_reveal: CardReveal,
+    },
+    ///the other player takes the turn after the 2 cards did not match
+    TakeTurn,
+}
+
+///what happened after an action was applied
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    ///a card was flipped face up. Its value can be still unknown.
+    CardFlipped {
+        ///card index
+        card_index: usize,
+        ///1 for the first click, 2 for the second click inside one turn
+        count_click_inside_one_turn: usize,
+    },
+    ///the value of a face up card is known. The image can be shown and the sound played.
+    CardRevealed {
+        ///card index
+        card_index: usize,
+    },
+    ///the revealed value does not match the commitment. It is not used.
+    RevealRejected {
+        ///card index
+        card_index: usize,
+    },
+    ///the 2 opened cards match. They stay face up and the player continues.
+    PairMatched {
+        ///the player that receives the point
+        player: usize,
+    },
+    ///the 2 opened cards do not match. The other player must take the turn.
+    PairNotMatched,
+    ///the other player took the turn. The 2 cards are face down again.
+    TurnChanged {
+        ///whose turn is now
+        player_turn: usize,
+    },
+}
+
+///the end of the game: who found the most pairs
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameResult {
+    ///one player has the most points
+    Winner {
+        ///the player number of the winner
+        player: usize,
+        ///the points of the winner
+        points: usize,
+    },
+    ///more players share the most points
+    Draw {
+        ///the player numbers with the most points
+        vec_players: Vec<usize>,
+        ///the points of every player in the draw
+        points: usize,
+    },
+}
+
+///why a move of a player breaks the rules
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RuleViolation {
+    ///the card index is not in the grid or it is the reserved index 0
+    CardIndexOutOfRange {
+        ///card index
+        card_index: usize,
+    },
+    ///the player moved when it was not allowed
+    NotYourTurn {
+        ///the player that moved
+        player: usize,
+    },
+    ///the card is already face up
+    CardAlreadyFaceUp {
+        ///card index
+        card_index: usize,
+    },
+    ///the 2 cards are open. The next move is the take turn.
+    ThirdClick,
+    ///the click count of the player differs from the click count here
+    ClickCountMismatch {
+        ///the click count after this click
+        expected: usize,
+        ///the click count in the message
+        received: usize,
+    },
+    ///the turn can be taken only after 2 revealed cards that did not match
+    TurnNotOver,
+}
+
+///why the deck of cards cannot be built
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DeckError {
+    ///the count of cards is odd or there are more pairs than different images
+    GridNotPossible {
+        ///rows and columns of the card grid
+        grid_size: GridSize,
+        ///count of different pairs in the content pack
+        card_images_count: usize,
+    },
+    ///the random generator works with u32
+    TooManyImages {
+        ///count of different pairs in the content pack
+        card_images_count: usize,
+    },
+    ///the salts of the commitments need the random generator of the system
+    NoSystemRandom,
+}
+
+///game rules and the game state they change
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameEngine {
+    ///rows and columns of the card grid
+    pub grid_size: GridSize,
+    ///the deck of cards is built from this parameters. Only the deck owner knows them.
+    pub deck_parameters: Option<DeckParameters>,
+    ///vector of cards
+    pub vec_cards: Vec<Card>,
+    ///commitment for every card. Index 0 is reserved for FaceDown and is empty.
+    pub vec_card_commitments: Vec<String>,
+    ///salt for every card. Only the deck owner has them.
+    vec_card_salts: Vec<String>,
+    ///the pairs of different faces from the content pack. Empty if a pair is 2 identical faces.
+    #[serde(default)]
+    pub vec_pairs: Vec<CardPair>,
+    //First turn: Player1 clicks 2 times and opens 2 cards.
+    //If cards match, Player1 receives one point and countinues: 2 click for 2 cards.
+    //If not match: Player2 clicks the Change button to close opened cards.
+    //Then starts the Player2 turn.
+    ///count click inside one turn
+    pub count_click_inside_one_turn: usize,
+    ///card index of first click
+    pub card_index_of_first_click: usize,
+    ///card index of second click
+    pub card_index_of_second_click: usize,
+    ///counts only clicks that flip the card. The third click is not counted.
+    pub count_all_clicks: usize,
+    ///whose turn is now: from player 1 to player_count
+    pub player_turn: usize,
+    ///1 for the solo mode, where the player takes the turn again after 2 cards did not match.
+    ///The players take turns in the order of their player numbers.
+    #[serde(default = "two_players")]
+    pub player_count: usize,
+    ///the points of every player. The index 0 is player 1.
+    pub vec_points: Vec<usize>,
+    ///the player that started the game. The rematch starts with the next player.
+    #[serde(default = "first_player")]
+    pub starting_player: usize,
+}
+//endregion
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleViolation::CardIndexOutOfRange { card_index } => {
+                write!(f, "the card {} is not in the grid", card_index)
+            }
+            RuleViolation::NotYourTurn { player } => {
+                write!(f, "player {} moved out of turn", player)
+            }
+            RuleViolation::CardAlreadyFaceUp { card_index } => {
+                write!(f, "the card {} is already face up", card_index)
+            }
+            RuleViolation::ThirdClick => write!(f, "a third click inside one turn"),
+            RuleViolation::ClickCountMismatch { expected, received } => {
+                write!(f, "the click count is {} instead of {}", received, expected)
+            }
+            RuleViolation::TurnNotOver => write!(f, "the turn cannot be taken now"),
+        }
+    }
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckError::GridNotPossible {
+                grid_size,
+                card_images_count,
+            } => write!(
+                f,
+                "the grid {}x{} is not possible with {} different images",
+                grid_size.rows, grid_size.cols, card_images_count
+            ),
+            DeckError::TooManyImages { card_images_count } => write!(
+                f,
+                "{} different images are too many for the random generator",
+                card_images_count
+            ),
+            DeckError::NoSystemRandom => {
+                write!(f, "the random generator of the system is not available")
+            }
+        }
+    }
+}
+
+impl DeckParameters {
+    ///new deck parameters with a random seed. The only place where the entropy is used.
+    pub fn new_random(grid_size: GridSize, card_images_count: usize) -> Self {
+        let mut rng = SmallRng::from_entropy();
+        DeckParameters {
+            seed: rng.gen(),
+            grid_size,
+            card_images_count,
+        }
+    }
+}
+
+///the commitment is the sha256 of the salt and the card number.
+///It does not show the card number, but the deck owner cannot change the card number later.
+pub fn card_commitment(salt: &str, card_number_and_img_src: usize) -> String {
+    let hash = Sha256::digest(format!("{}:{}", salt, card_number_and_img_src).as_bytes());
+    hex_string(&hash)
+}
+
+///the hash as lowercase hex digits
+fn hex_string(hash: &[u8]) -> String {
+    hash.iter().fold(String::new(), |mut text, byte| {
+        let _ = write!(text, "{:02x}", byte);
+        text
+    })
+}
+
+///the games saved before the solo mode were always for 2 players
+fn two_players() -> usize {
+    2
+}
+
+///the games saved before the rematch were always started by player 1
+fn first_player() -> usize {
+    1
+}
+
+///random index from 0 to upper exclusive.
+///The random generator works with u32 and not with usize,
+///so the result is the same on wasm32 and on 64 bit machines.
+fn gen_index(rng: &mut Pcg32, upper: usize) -> Result<usize, DeckError> {
+    let too_many_images = DeckError::TooManyImages {
+        card_images_count: upper,
+    };
+    let upper = u32::try_from(upper).map_err(|_| too_many_images)?;
+    usize::try_from(rng.gen_range(0, upper)).map_err(|_| too_many_images)
+}
+
+impl GameEngine {
+    ///constructor with a new deck of cards.
+    ///The same deck parameters always build the same deck on all machines.
+    pub fn new(deck_parameters: DeckParameters) -> Result<Self, DeckError> {
+        GameEngine::new_with_pairs(deck_parameters, Vec::new())
+    }
+    ///constructor with a new deck of pairs of different faces.
+    ///The card_images_count of the deck parameters is the count of the pairs.
+    ///Empty pairs build the classic deck with 2 identical faces for every pair.
+    ///The grid must be possible, else the different random images would never be enough.
+    pub fn new_with_pairs(
+        deck_parameters: DeckParameters,
+        vec_pairs: Vec<CardPair>,
+    ) -> Result<Self, DeckError> {
+        if !deck_parameters
+            .grid_size
+            .is_possible(deck_parameters.card_images_count)
+        {
+            return Err(DeckError::GridNotPossible {
+                grid_size: deck_parameters.grid_size,
+                card_images_count: deck_parameters.card_images_count,
+            });
+        }
+        //Pcg32 is the same on every platform. SmallRng is not.
+        let mut rng = Pcg32::seed_from_u64(deck_parameters.seed);
+        //region: find distinct random numbers between 1 and card_images_count for the alphabet cards
+        //vec_of_random_numbers is 0 based
+        let mut vec_of_random_numbers = Vec::new();
+        let mut i = 0;
+        while i < deck_parameters.grid_size.pair_count() {
+            //gen_index is lower inclusive, upper exclusive. The card number 0 is the card face down.
+            let num: usize = gen_index(&mut rng, deck_parameters.card_images_count)? + 1;
+            if vec_of_random_numbers.contains(&num) {
+                //do nothing if the random number is repeated
+            } else {
+                vec_of_random_numbers.push(num);
+                i += 1;
+            }
+        }
+        //push the 2 faces of every pair: the same number or the numbers of the card pair
+        let mut vec_of_random_numbers: Vec<usize> = vec_of_random_numbers
+            .iter()
+            .flat_map(|num| match vec_pairs.get(num - 1) {
+                Some(card_pair) => vec![card_pair.first, card_pair.second],
+                None => vec![*num, *num],
+            })
+            .collect();
+        //endregion
+
+        //region: shuffle the numbers
+        //the Fisher-Yates shuffle like in rand::seq::SliceRandom, but with gen_index
+        for i in (1..vec_of_random_numbers.len()).rev() {
+            let j = gen_index(&mut rng, i + 1)?;
+            vec_of_random_numbers.swap(i, j);
+        }
+        //endregion
+
+        //region: create Cards from random numbers
+        let mut vec_cards = Vec::new();
+
+        //Index 0 is special and reserved for FaceDown. Cards start with base 1
+        let new_card = Card {
+            status: CardStatusCardFace::Down,
+            card_number_and_img_src: 0,
+            card_index_and_id: 0,
+        };
+        vec_cards.push(new_card);
+
+        //create all the cards and push to the vector
+        //card base index will be 1. 0 is reserved for FaceDown.
+        for (card_index_and_id, random_number) in (1..).zip(vec_of_random_numbers.iter()) {
+            let new_card = Card {
+                status: CardStatusCardFace::Down,
+                //dereference random number from iterator
+                card_number_and_img_src: *random_number,
+                card_index_and_id,
+            };
+            vec_cards.push(new_card);
+        }
+        //endregion
+
+        //region: commit to the cards
+        //the salts must not be predictable, so they don't come from the seeded generator
+        let mut os_rng = OsRng::new().map_err(|_| DeckError::NoSystemRandom)?;
+        let mut vec_card_salts = Vec::new();
+        let mut vec_card_commitments = Vec::new();
+        for card in &vec_cards {
+            if card.card_index_and_id == 0 {
+                vec_card_salts.push(String::new());
+                vec_card_commitments.push(String::new());
+            } else {
+                let salt = format!("{:016x}{:016x}", os_rng.gen::<u64>(), os_rng.gen::<u64>());
+                vec_card_commitments.push(card_commitment(&salt, card.card_number_and_img_src));
+                vec_card_salts.push(salt);
+            }
+        }
+        //endregion
+
+        Ok(GameEngine {
+            grid_size: deck_parameters.grid_size,
+            deck_parameters: Some(deck_parameters),
+            vec_cards,
+            vec_card_commitments,
+            vec_card_salts,
+            vec_pairs,
+            count_click_inside_one_turn: 0,
+            card_index_of_first_click: 0,
+            card_index_of_second_click: 0,
+            count_all_clicks: 0,
+            player_turn: 0, //nobody plays until WantToPlay+Accept
+            player_count: 2,
+            vec_points: vec![0; 2],
+            starting_player: 1,
+        })
+    }
+    ///constructor for the player that is not the deck owner.
+    ///All the cards are hidden and only the commitments are known.
+    ///The caller sets the pairs of different faces from its own content pack.
+    pub fn from_commitments(grid_size: GridSize, vec_card_commitments: Vec<String>) -> Self {
+        let vec_cards = (0..vec_card_commitments.len())
+            .map(|card_index_and_id| Card {
+                status: CardStatusCardFace::Down,
+                card_number_and_img_src: 0,
+                card_index_and_id,
+            })
+            .collect();
+        GameEngine {
+            grid_size,
+            deck_parameters: None,
+            vec_cards,
+            vec_card_commitments,
+            vec_card_salts: Vec::new(),
+            vec_pairs: Vec::new(),
+            count_click_inside_one_turn: 0,
+            card_index_of_first_click: 0,
+            card_index_of_second_click: 0,
+            count_all_clicks: 0,
+            player_turn: 0, //nobody plays until WantToPlay+Accept
+            player_count: 2,
+            vec_points: vec![0; 2],
+            starting_player: 1,
+        }
+    }
+    ///the deck owner can reveal a face up card to the other player
+    pub fn reveal(&self, card_index: usize) -> Option<CardReveal> {
+        let card = self.vec_cards.get(card_index)?;
+        let salt = self.vec_card_salts.get(card_index)?;
+        if card_index == 0 || card.status == CardStatusCardFace::Down {
+            return None;
+        }
+        Some(CardReveal {
+            card_index,
+            card_number_and_img_src: card.card_number_and_img_src,
+            salt: salt.to_string(),
+        })
+    }
+    ///the card number is known to this player
+    pub fn is_card_revealed(&self, card_index: usize) -> bool {
+        self.vec_cards
+            .get(card_index)
+            .is_some_and(|card| card.card_number_and_img_src != 0)
+    }
+    ///hash of the public game state: card statuses, face up card numbers, turn, counters and points.
+    ///None while a face up card is not revealed yet to this player,
+    ///because then the players cannot have the same state.
+    pub fn state_hash(&self) -> Option<String> {
+        let mut state = String::new();
+        for card in &self.vec_cards {
+            if card.status == CardStatusCardFace::Down {
+                state.push_str("D,");
+            } else if self.is_card_revealed(card.card_index_and_id) {
+                state.push_str(&format!(
+                    "{:?}{},",
+                    card.status, card.card_number_and_img_src
+                ));
+            } else {
+                return None;
+            }
+        }
+        state.push_str(&format!(
+            "{}:{}:{}:{}:{}:{:?}",
+            self.count_click_inside_one_turn,
+            self.card_index_of_first_click,
+            self.card_index_of_second_click,
+            self.count_all_clicks,
+            self.player_turn,
+            self.vec_points
+        ));
+        let hash = Sha256::digest(state.as_bytes());
+        Some(hex_string(&hash))
+    }
+    ///snapshot of the public game state. Only the deck owner can reveal the face up cards.
+    pub fn snapshot(&self) -> Option<GameSnapshot> {
+        if self.vec_card_salts.is_empty() {
+            return None;
+        }
+        let vec_card_reveals = self
+            .vec_cards
+            .iter()
+            .filter_map(|card| self.reveal(card.card_index_and_id))
+            .collect();
+        Some(GameSnapshot {
+            vec_card_statuses: self.vec_cards.iter().map(|card| card.status).collect(),
+            vec_card_reveals,
+            count_click_inside_one_turn: self.count_click_inside_one_turn,
+            card_index_of_first_click: self.card_index_of_first_click,
+            card_index_of_second_click: self.card_index_of_second_click,
+            count_all_clicks: self.count_all_clicks,
+            player_turn: self.player_turn,
+            vec_points: self.vec_points.clone(),
+        })
+    }
+    ///replace the local game state with the snapshot from the deck owner.
+    ///The reveals are verified against the commitments.
+    ///Every card face up needs its reveal, so nobody can turn cards face up without the values.
+    ///Returns false and changes nothing if the snapshot does not fit this deck.
+    ///The count of players comes with the points.
+    pub fn restore_snapshot(&mut self, snapshot: &GameSnapshot) -> bool {
+        if snapshot.vec_card_statuses.len() != self.vec_cards.len()
+            || snapshot.vec_points.is_empty()
+            || snapshot.player_turn == 0
+            || snapshot.player_turn > snapshot.vec_points.len()
+            || snapshot.card_index_of_first_click >= self.vec_cards.len()
+            || snapshot.card_index_of_second_click >= self.vec_cards.len()
+        {
+            return false;
+        }
+        for card_reveal in &snapshot.vec_card_reveals {
+            let commitment =
+                card_commitment(&card_reveal.salt, card_reveal.card_number_and_img_src);
+            if card_reveal.card_index == 0
+                || self.vec_card_commitments.get(card_reveal.card_index) != Some(&commitment)
+            {
+                return false;
+            }
+        }
+        let is_every_face_up_card_revealed = snapshot
+            .vec_card_statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, status)| **status != CardStatusCardFace::Down)
+            .all(|(card_index, _)| {
+                snapshot
+                    .vec_card_reveals
+                    .iter()
+                    .any(|card_reveal| card_reveal.card_index == card_index)
+            });
+        if !is_every_face_up_card_revealed {
+            return false;
+        }
+        for (card, status) in self.vec_cards.iter_mut().zip(&snapshot.vec_card_statuses) {
+            card.status = *status;
+        }
+        for card_reveal in &snapshot.vec_card_reveals {
+            if let Some(card) = self.vec_cards.get_mut(card_reveal.card_index) {
+                card.card_number_and_img_src = card_reveal.card_number_and_img_src;
+            }
+        }
+        self.count_click_inside_one_turn = snapshot.count_click_inside_one_turn;
+        self.card_index_of_first_click = snapshot.card_index_of_first_click;
+        self.card_index_of_second_click = snapshot.card_index_of_second_click;
+        self.count_all_clicks = snapshot.count_all_clicks;
+        self.player_turn = snapshot.player_turn;
+        self.set_player_count(snapshot.vec_points.len());
+        self.vec_points = snapshot.vec_points.clone();
+        true
+    }
+    ///the count of players before the start. Every player starts with 0 points.
+    pub fn set_player_count(&mut self, player_count: usize) {
+        self.player_count = player_count.max(1);
+        self.vec_points = vec![0; self.player_count];
+    }
+    ///the points of the player. 0 for a player that is not in the game.
+    pub fn points(&self, player: usize) -> usize {
+        player
+            .checked_sub(1)
+            .and_then(|index| self.vec_points.get(index))
+            .copied()
+            .unwrap_or(0)
+    }
+    ///the player after the player in turn. In the solo mode it is the same player.
+    pub fn next_player(&self) -> usize {
+        self.player_turn % self.player_count + 1
+    }
+    ///the play starts with player 1
+    pub fn start(&mut self) {
+        self.start_with_player(1);
+    }
+    ///the play starts with this player. A player that is not in the game is replaced by player 1.
+    pub fn start_with_player(&mut self, player: usize) {
+        self.starting_player = if player == 0 || player > self.player_count {
+            1
+        } else {
+            player
+        };
+        self.player_turn = self.starting_player;
+    }
+    ///the rematch starts with the player after the one that started this game
+    pub fn next_starting_player(&self) -> usize {
+        self.starting_player % self.player_count + 1
+    }
+    ///the player can click on this card now
+    pub fn validate_click(&self, player: usize, card_index: usize) -> Result<(), RuleViolation> {
+        if player != self.player_turn {
+            return Err(RuleViolation::NotYourTurn { player });
+        }
+        let card = match self.vec_cards.get(card_index) {
+            Some(card) if card_index != 0 => card,
+            _ => return Err(RuleViolation::CardIndexOutOfRange { card_index }),
+        };
+        if self.count_click_inside_one_turn >= 2 {
+            return Err(RuleViolation::ThirdClick);
+        }
+        if card.status != CardStatusCardFace::Down {
+            return Err(RuleViolation::CardAlreadyFaceUp { card_index });
+        }
+        Ok(())
+    }
+    ///the click of the other player is valid and its click count follows the click count here
+    pub fn validate_remote_click(
+        &self,
+        player: usize,
+        card_index: usize,
+        count_click_inside_one_turn: usize,
+    ) -> Result<(), RuleViolation> {
+        self.validate_click(player, card_index)?;
+        let expected = self.count_click_inside_one_turn + 1;
+        if count_click_inside_one_turn != expected {
+            return Err(RuleViolation::ClickCountMismatch {
+                expected,
+                received: count_click_inside_one_turn,
+            });
+        }
+        Ok(())
+    }
+    ///only the next player can take the turn, after 2 revealed cards that did not match
+    pub fn validate_take_turn(&self, player: usize) -> Result<(), RuleViolation> {
+        if player != self.next_player() || self.player_count < 2 {
+            return Err(RuleViolation::NotYourTurn { player });
+        }
+        if self.count_click_inside_one_turn < 2
+            || !self.is_pair_revealed()
+            || self.is_pair_matched()
+        {
+            return Err(RuleViolation::TurnNotOver);
+        }
+        Ok(())
+    }
+    ///apply the action to the game state and return what happened.
+    ///An action that is not possible now changes nothing and returns no events.
+    pub fn apply(&mut self, action: GameAction) -> Vec<GameEvent> {
+        match action {
+            GameAction::Click { card_index } => self.click(card_index),
+            GameAction::Reveal { card_reveal } => self.reveal_card(&card_reveal),
+            GameAction::TakeTurn => self.take_turn(),
+        }
+    }
+    ///flip the card and check for a match on the second click
+    fn click(&mut self, card_index: usize) -> Vec<GameEvent> {
+        let mut vec_events = Vec::new();
+        //the zero card is reserved for FaceDown. Only 2 clicks inside one turn.
+        if card_index == 0 || self.count_click_inside_one_turn >= 2 {
+            return vec_events;
+        }
+        //click is usefull only on facedown cards
+        match self.vec_cards.get(card_index) {
+            Some(card) if card.status == CardStatusCardFace::Down => {}
+            _ => return vec_events,
+        }
+
+        //the begining of the turn is count_click_inside_one_turn=0
+        //on click imediately increase that. So first click is 1 and second click is 2.
+        self.count_click_inside_one_turn += 1;
+        self.count_all_clicks += 1;
+        if self.count_click_inside_one_turn == 1 {
+            self.card_index_of_first_click = card_index;
+            self.card_index_of_second_click = 0;
+        } else {
+            self.card_index_of_second_click = card_index;
+        }
+        self.set_status(card_index, CardStatusCardFace::UpTemporary);
+        vec_events.push(GameEvent::CardFlipped {
+            card_index,
+            count_click_inside_one_turn: self.count_click_inside_one_turn,
+        });
+
+        //the value of the card can be still unknown to this player
+        if self.is_card_revealed(card_index) {
+            vec_events.push(GameEvent::CardRevealed { card_index });
+        }
+        self.check_pair(&mut vec_events);
+        vec_events
+    }
+    ///verify the revealed card against the commitment and then use the card number
+    fn reveal_card(&mut self, card_reveal: &CardReveal) -> Vec<GameEvent> {
+        let card_index = card_reveal.card_index;
+        //a card that is already known does not need a reveal
+        if card_index == 0
+            || card_index >= self.vec_cards.len()
+            || self.is_card_revealed(card_index)
+        {
+            return Vec::new();
+        }
+        let commitment = card_commitment(&card_reveal.salt, card_reveal.card_number_and_img_src);
+        if self.vec_card_commitments.get(card_index) != Some(&commitment) {
+            return vec![GameEvent::RevealRejected { card_index }];
+        }
+        let mut vec_events = Vec::new();
+        if let Some(card) = self.vec_cards.get_mut(card_index) {
+            card.card_number_and_img_src = card_reveal.card_number_and_img_src;
+            if card.status != CardStatusCardFace::Down {
+                vec_events.push(GameEvent::CardRevealed { card_index });
+            }
+        }
+        //the pair can be checked only when both cards are revealed
+        if card_index == self.card_index_of_first_click
+            || card_index == self.card_index_of_second_click
+        {
+            self.check_pair(&mut vec_events);
+        }
+        vec_events
+    }
+    ///after the second click and when both cards are revealed, check for card match
+    fn check_pair(&mut self, vec_events: &mut Vec<GameEvent>) {
+        if self.count_click_inside_one_turn != 2 || !self.is_pair_revealed() {
+            return;
+        }
+        //if the cards match, player get one point and continues another turn
+        if self.is_pair_matched() {
+            //give points
+            if let Some(points) = self
+                .player_turn
+                .checked_sub(1)
+                .and_then(|index| self.vec_points.get_mut(index))
+            {
+                *points += 1;
+            }
+            // the two cards matches. make them permanent FaceUp
+            self.set_status(
+                self.card_index_of_first_click,
+                CardStatusCardFace::UpPermanently,
+            );
+            self.set_status(
+                self.card_index_of_second_click,
+                CardStatusCardFace::UpPermanently,
+            );
+            self.count_click_inside_one_turn = 0;
+            vec_events.push(GameEvent::PairMatched {
+                player: self.player_turn,
+            });
+        } else {
+            vec_events.push(GameEvent::PairNotMatched);
+        }
+    }
+    ///the next player takes the turn and closes the 2 cards that did not match
+    fn take_turn(&mut self) -> Vec<GameEvent> {
+        //the turn can be taken only after 2 revealed cards that did not match
+        if self.count_click_inside_one_turn < 2
+            || !self.is_pair_revealed()
+            || self.is_pair_matched()
+        {
+            return Vec::new();
+        }
+        //the next player. In the solo mode it is the same player.
+        self.player_turn = self.next_player();
+
+        //close first and second card
+        self.set_status(self.card_index_of_first_click, CardStatusCardFace::Down);
+        self.set_status(self.card_index_of_second_click, CardStatusCardFace::Down);
+        self.card_index_of_first_click = 0;
+        self.card_index_of_second_click = 0;
+        self.count_click_inside_one_turn = 0;
+        vec![GameEvent::TurnChanged {
+            player_turn: self.player_turn,
+        }]
+    }
+    ///all the cards are face up permanently
+    pub fn is_game_over(&self) -> bool {
+        self.vec_cards.len() > 1
+            && self
+                .vec_cards
+                .iter()
+                .skip(1)
+                .all(|card| card.status == CardStatusCardFace::UpPermanently)
+    }
+    ///the winner or the draw when the game is over. None while the game is in progress.
+    pub fn game_result(&self) -> Option<GameResult> {
+        if !self.is_game_over() {
+            return None;
+        }
+        let points = self.vec_points.iter().copied().max().unwrap_or(0);
+        let vec_players: Vec<usize> = (1..=self.vec_points.len())
+            .filter(|player| self.points(*player) == points)
+            .collect();
+        if vec_players.len() == 1 {
+            Some(GameResult::Winner {
+                player: vec_players[0],
+                points,
+            })
+        } else {
+            Some(GameResult::Draw {
+                vec_players,
+                points,
+            })
+        }
+    }
+    ///the values of both opened cards are known
+    pub fn is_pair_revealed(&self) -> bool {
+        self.is_card_revealed(self.card_index_of_first_click)
+            && self.is_card_revealed(self.card_index_of_second_click)
+    }
+    ///the first and the second opened card are a pair
+    pub fn is_pair_matched(&self) -> bool {
+        match (
+            self.vec_cards.get(self.card_index_of_first_click),
+            self.vec_cards.get(self.card_index_of_second_click),
+        ) {
+            (Some(first), Some(second)) => self.is_pair(
+                first.card_number_and_img_src,
+                second.card_number_and_img_src,
+            ),
+            _ => false,
+        }
+    }
+    ///the 2 card numbers are the same face or the 2 faces of a card pair. 0 is never a pair.
+    pub fn is_pair(&self, card_number_1: usize, card_number_2: usize) -> bool {
+        card_number_1 != 0
+            && (card_number_1 == card_number_2
+                || self
+                    .vec_pairs
+                    .iter()
+                    .any(|card_pair| card_pair.matches(card_number_1, card_number_2)))
+    }
+    ///change the status of one card. Index out of range changes nothing.
+    fn set_status(&mut self, card_index: usize, status: CardStatusCardFace) {
+        if let Some(card) = self.vec_cards.get_mut(card_index) {
+            card.status = status;
+        }
+    }
+}
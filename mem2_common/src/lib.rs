@@ -0,0 +1,518 @@
+//! mem2_common - the types shared between the mem2 client, the mem2_server and any native tooling.
+//! The `WsMessage` is the WebSocket protocol. Every message is json made by serde.
+//! The cards and the grid size are part of the messages.
+//! The content packs are listed in the manifest `content/packs.json`.
+//! It is `no_std` with `alloc`, so it compiles for wasm32 and for every native target.
+//! The feature `engine` adds the rules of the game in the module `gameengine`.
+//! The engine needs std, rand and sha2. The client and the server authoritative mode use it.
+//! The feature `engine` adds the computer opponent in the module `computerplayer` too.
+//! The module `reliablechannel` orders, acknowledges and resends the game messages.
+
+#![cfg_attr(not(feature = "engine"), no_std)]
+
+//region: extern and use statements
+extern crate alloc;
+
+#[cfg(feature = "engine")]
+pub mod computerplayer;
+#[cfg(feature = "engine")]
+pub mod gameengine;
+pub mod reliablechannel;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+//endregion
+
+//region: enum, structs, const,...
+///count of card images in the content folder. The card face down image is not counted.
+pub const CARD_IMAGES_COUNT: usize = 26;
+
+///the manifest of the content packs, relative to the web root
+pub const PACKS_MANIFEST_PATH: &str = "content/packs.json";
+
+///the most players in one room. Only the server can be the referee for more than 2 players.
+pub const MAX_PLAYERS: usize = 6;
+
+///the grid sizes the players can choose from
+pub const GRID_SIZES: [GridSize; 5] = [
+    GridSize { rows: 2, cols: 3 },
+    GridSize { rows: 3, cols: 4 },
+    GridSize { rows: 4, cols: 4 },
+    GridSize { rows: 4, cols: 6 },
+    GridSize { rows: 6, cols: 6 },
+];
+
+///rows and columns of the card grid. Agreed between the players before the play.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct GridSize {
+    ///count of rows
+    pub rows: usize,
+    ///count of columns
+    pub cols: usize,
+}
+
+///one player in a room. The player number is the position in the list of the room plus 1.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RoomPlayer {
+    ///ws client instance unique id of the player
+    pub ws_client_instance: usize,
+    ///the name the player typed. Empty if the player did not type it.
+    pub player_name: String,
+}
+
+///the manifest `content/packs.json` lists the content packs the players can choose from
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct PacksManifest {
+    ///all the content packs
+    pub packs: Vec<ContentPack>,
+}
+
+///one content pack: the images, sounds and names of the cards in one folder
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ContentPack {
+    ///the name shown in the pack picker
+    pub name: String,
+    ///the folder in the web root, like content02. Both players use the same folder.
+    pub folder: String,
+    ///count of different pairs: the card images in a classic pack,
+    ///the pair ids in a pack with pairs of different faces. The card face down image is not counted.
+    pub card_count: usize,
+    ///the card face down image, relative to the folder
+    pub card_back: String,
+    ///the json file with the `PackCards`, relative to the folder
+    pub cards_file: String,
+}
+
+///the cards of one content pack. The card number 1 is the first card in the vector.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct PackCards {
+    ///the metadata of every card
+    pub cards: Vec<CardMetadata>,
+}
+
+///everything about one card face. The paths are relative to the folder of the pack.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CardMetadata {
+    ///the image of the card face
+    pub image: String,
+    ///the sound played when the card is flipped. Some cards are silent.
+    pub sound: Option<String>,
+    ///the name shown in the header when the card is face up
+    pub label: String,
+    ///the alt text of the image
+    #[serde(default)]
+    pub alt: String,
+    ///the pronunciation or a longer description, shown as a tooltip
+    pub description: Option<String>,
+    ///the 2 cards with the same pair id are a pair of different faces, like a letter and a picture.
+    ///Without pair ids every card is a pair with itself: 2 identical faces.
+    pub pair_id: Option<String>,
+}
+
+///the card numbers of the 2 faces of a pair. In a classic pack it is the same card number.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct CardPair {
+    ///the card number of the first face
+    pub first: usize,
+    ///the card number of the second face
+    pub second: usize,
+}
+
+///the 3 possible states of one card
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum CardStatusCardFace {
+    ///card face down
+    Down,
+    ///card face Up Temporary
+    UpTemporary,
+    ///card face up Permanently
+    UpPermanently,
+}
+
+///all the data for one card
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Card {
+    ///card status
+    pub status: CardStatusCardFace,
+    ///field for src attribute for HTML element imagea and filename of card image.
+    ///0 if the card is not revealed yet to this player.
+    pub card_number_and_img_src: usize,
+    ///field for id attribute for HTML element image contains the card index
+    pub card_index_and_id: usize,
+}
+
+///the secret of one card. The deck owner sends it when the card is flipped.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CardReveal {
+    ///card index
+    pub card_index: usize,
+    ///card number
+    pub card_number_and_img_src: usize,
+    ///random salt, so the card number cannot be guessed from the commitment
+    pub salt: String,
+}
+
+///the public game state. The deck owner sends it to resynchronise the other player.
+///The face down cards stay secret, the face up cards come with their reveal.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct GameSnapshot {
+    ///status of every card. Index 0 is reserved for FaceDown.
+    pub vec_card_statuses: Vec<CardStatusCardFace>,
+    ///the reveal of every face up card
+    pub vec_card_reveals: Vec<CardReveal>,
+    ///count click inside one turn
+    pub count_click_inside_one_turn: usize,
+    ///card index of first click
+    pub card_index_of_first_click: usize,
+    ///card index of second click
+    pub card_index_of_second_click: usize,
+    ///counts only clicks that flip the card
+    pub count_all_clicks: usize,
+    ///whose turn is now: from player 1 to the count of players
+    pub player_turn: usize,
+    ///the points of every player. The index 0 is player 1.
+    pub vec_points: Vec<usize>,
+}
+
+///`WsMessage` enum for websocket
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum WsMessage {
+    ///connection test
+    ConnectionTest {
+        ///anything
+        test: String,
+    },
+    ///the first message after the connection is opened. It negotiates the protocol.
+    Hello {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///version of the WebSocket protocol
+        protocol_version: usize,
+        ///features of the WebSocket protocol
+        features: Vec<String>,
+        ///the reply to a Hello is not replied again
+        is_reply: bool,
+    },
+    ///want to play. The room is open and shown in the lobby of the other players.
+    WantToPlay {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///the code of the room created by the player that asks
+        room_code: String,
+        ///grid size chosen by the player that asks
+        grid_size: GridSize,
+        ///the server owns the deck and the rules engine. The clients only render the server state.
+        is_server_authoritative: bool,
+        ///the folder of the content pack chosen by the player that asks
+        content_folder_name: String,
+        ///the name of the player that asks
+        player_name: String,
+    },
+    /// accept play. The room is full and it disappears from the lobby.
+    AcceptPlay {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///the code of the room
+        room_code: String,
+        ///grid size of the cards
+        grid_size: GridSize,
+        ///the commitments of all cards. The card values are revealed one by one when flipped.
+        vec_card_commitments: Vec<String>,
+        ///the folder of the content pack of the room
+        content_folder_name: String,
+        ///the name of the player that accepts
+        player_name: String,
+    },
+    ///server authoritative mode: the player joins the room and waits until the host starts the game.
+    ///Up to `MAX_PLAYERS` players join the same room.
+    JoinRoom {
+        ///ws client instance unique id of the player that joins
+        ws_client_instance: usize,
+        ///the code of the room
+        room_code: String,
+        ///the name of the player that joins
+        player_name: String,
+    },
+    ///server authoritative mode: the players in the room after every join, from the server
+    RoomPlayers {
+        ///the code of the room
+        room_code: String,
+        ///the host is the first, then the players in the order they joined
+        vec_room_players: Vec<RoomPlayer>,
+    },
+    ///server authoritative mode: the host starts the game with the players that joined.
+    ///The room disappears from the lobby.
+    StartGame {
+        ///ws client instance unique id of the host
+        ws_client_instance: usize,
+        ///the code of the room
+        room_code: String,
+    },
+    ///player click
+    PlayerClick {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///card_index
+        card_index: usize,
+        ///count click inside one turn
+        count_click_inside_one_turn: usize,
+        ///the deck owner reveals the card with the click
+        card_reveal: Option<CardReveal>,
+        ///hash of the game state after the click
+        state_hash: Option<String>,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///the deck owner reveals the card the other player clicked
+    CardReveal {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///card index, card number and salt
+        card_reveal: CardReveal,
+        ///hash of the game state after the reveal
+        state_hash: Option<String>,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///player change
+    PlayerChange {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///hash of the game state after the player change
+        state_hash: Option<String>,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///the game states differ. The deck owner must send the snapshot.
+    ResyncRequest {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///the public game state from the deck owner that replaces the local game state
+    ResyncSnapshot {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///card statuses, face up card reveals, turn, counters and points
+        game_snapshot: GameSnapshot,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///the player reconnected and continues the game in progress with the same other player
+    Rejoin {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///the other player of the game in progress
+        other_ws_client_instance: usize,
+    },
+    ///acknowledgement of a received game message
+    Ack {
+        ///ws client instance unique id. To not listen the echo to yourself.
+        ws_client_instance: usize,
+        ///sequence number of the received game message
+        seq: u64,
+    },
+    ///server authoritative mode: the player clicked a card. The server validates and applies it.
+    ServerClick {
+        ///ws client instance unique id of the player that clicked
+        ws_client_instance: usize,
+        ///card index
+        card_index: usize,
+    },
+    ///server authoritative mode: the player takes the turn. The server validates and applies it.
+    ServerTakeTurn {
+        ///ws client instance unique id of the player that takes the turn
+        ws_client_instance: usize,
+    },
+    ///server authoritative mode: the game state after every move. It replaces the client state.
+    ServerState {
+        ///the code of the room
+        room_code: String,
+        ///grid size of the cards
+        grid_size: GridSize,
+        ///the commitments of all cards
+        vec_card_commitments: Vec<String>,
+        ///card statuses, face up card reveals, turn, counters and points
+        game_snapshot: GameSnapshot,
+        ///the players in the order of their player numbers
+        vec_room_players: Vec<RoomPlayer>,
+    },
+    ///after the game over the player wants to play again in the same room.
+    ///When both players want it, the deck owner deals a fresh deck.
+    RematchRequest {
+        ///ws client instance unique id of the player that wants the rematch
+        ws_client_instance: usize,
+        ///the code of the room
+        room_code: String,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///the deck owner dealt a fresh deck for the rematch. It sends only the commitments.
+    Rematch {
+        ///ws client instance unique id of the deck owner
+        ws_client_instance: usize,
+        ///the code of the room
+        room_code: String,
+        ///grid size of the cards
+        grid_size: GridSize,
+        ///the commitments of all cards of the fresh deck
+        vec_card_commitments: Vec<String>,
+        ///the player after the one that started the game before
+        starting_player: usize,
+        ///sequence number of the game message
+        seq: u64,
+    },
+    ///server authoritative mode: after the game over the player wants to play again.
+    ///The server deals a fresh deck when all the players want it.
+    ServerRematchRequest {
+        ///ws client instance unique id of the player that wants the rematch
+        ws_client_instance: usize,
+    },
+}
+//endregion
+
+impl WsMessage {
+    ///the sender and the sequence number of a game message.
+    ///None for messages that are not part of the ordered game stream.
+    pub fn sequence(&self) -> Option<(usize, u64)> {
+        match self {
+            WsMessage::PlayerClick {
+                ws_client_instance,
+                seq,
+                ..
+            }
+            | WsMessage::CardReveal {
+                ws_client_instance,
+                seq,
+                ..
+            }
+            | WsMessage::PlayerChange {
+                ws_client_instance,
+                seq,
+                ..
+            }
+            | WsMessage::ResyncRequest {
+                ws_client_instance,
+                seq,
+            }
+            | WsMessage::ResyncSnapshot {
+                ws_client_instance,
+                seq,
+                ..
+            }
+            | WsMessage::RematchRequest {
+                ws_client_instance,
+                seq,
+                ..
+            }
+            | WsMessage::Rematch {
+                ws_client_instance,
+                seq,
+                ..
+            } => Some((*ws_client_instance, *seq)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for GridSize {
+    ///the classic 4x4 grid with 8 pairs
+    fn default() -> Self {
+        GridSize { rows: 4, cols: 4 }
+    }
+}
+
+impl Default for ContentPack {
+    ///the pack in the folder content. It is used until the manifest is loaded.
+    fn default() -> Self {
+        ContentPack {
+            name: String::from("Alphabet"),
+            folder: String::from("content"),
+            card_count: CARD_IMAGES_COUNT,
+            card_back: String::from("img/mem_image_00_cardfacedown.png"),
+            cards_file: String::from("cards.json"),
+        }
+    }
+}
+
+impl PacksManifest {
+    ///the pack in this folder
+    pub fn find(&self, folder: &str) -> Option<&ContentPack> {
+        self.packs
+            .iter()
+            .find(|content_pack| content_pack.folder == folder)
+    }
+}
+
+impl PackCards {
+    ///the metadata of the card number. The card number 0 is the card back and has no metadata.
+    pub fn card(&self, card_number: usize) -> Option<&CardMetadata> {
+        card_number
+            .checked_sub(1)
+            .and_then(|index| self.cards.get(index))
+    }
+    ///the pairs of different faces in the order of the first card of every pair id.
+    ///Empty for a classic pack without pair ids. A pair id without exactly 2 cards is not a pair.
+    pub fn pairs(&self) -> Vec<CardPair> {
+        let mut vec_pairs = Vec::new();
+        for (index, card_metadata) in self.cards.iter().enumerate() {
+            let pair_id = match &card_metadata.pair_id {
+                Some(pair_id) => pair_id,
+                None => continue,
+            };
+            let vec_card_numbers: Vec<usize> = self
+                .cards
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| other.pair_id.as_ref() == Some(pair_id))
+                .map(|(other_index, _)| other_index + 1)
+                .collect();
+            //only the first card of the pair id adds the pair
+            if vec_card_numbers.len() == 2 && vec_card_numbers[0] == index + 1 {
+                vec_pairs.push(CardPair {
+                    first: vec_card_numbers[0],
+                    second: vec_card_numbers[1],
+                });
+            }
+        }
+        vec_pairs
+    }
+    ///count of different pairs the deck can be built from
+    pub fn pair_count(&self) -> usize {
+        if self
+            .cards
+            .iter()
+            .any(|card_metadata| card_metadata.pair_id.is_some())
+        {
+            self.pairs().len()
+        } else {
+            self.cards.len()
+        }
+    }
+}
+
+impl CardPair {
+    ///the 2 card numbers are the faces of this pair, in any order
+    pub fn matches(self, card_number_1: usize, card_number_2: usize) -> bool {
+        (self.first == card_number_1 && self.second == card_number_2)
+            || (self.first == card_number_2 && self.second == card_number_1)
+    }
+}
+
+impl GridSize {
+    ///count of all cards in the grid. The grid size comes from the other player,
+    ///so a grid too big for usize does not overflow. It is never possible.
+    pub fn card_count(self) -> usize {
+        self.rows.saturating_mul(self.cols)
+    }
+    ///every card has a pair
+    pub fn pair_count(self) -> usize {
+        self.card_count() / 2
+    }
+    ///only the grid sizes the players can choose from. Every pair needs a different image.
+    pub fn is_possible(self, card_images_count: usize) -> bool {
+        GRID_SIZES.contains(&self) && self.pair_count() <= card_images_count
+    }
+}
@@ -0,0 +1,121 @@
+//! Sequence numbers, acknowledgements and resend for the game messages over WebSocket.
+//! The WebSocket server only relays the messages. A message can be lost or come in the wrong order.
+//! Every game message gets a sequence number from the sender.
+//! The receiver acknowledges every message and delivers them to the game only in the right order.
+//! The sender resends the messages that are not acknowledged after a timeout.
+//! It knows nothing about WebSocket, the time comes as a parameter.
+//! It is `no_std` like the protocol, so it is tested natively with `cargo test`.
+
+//region: use statements
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+//endregion
+
+//region: enum, structs, const,...
+///milliseconds to wait for the acknowledgement before resending the message
+pub const RESEND_TIMEOUT_MS: f64 = 2000.0;
+///the receiver keeps only so many messages after the next one to deliver.
+///The messages after the window are dropped and not acknowledged, so the sender resends them later.
+pub const RECEIVE_WINDOW: u64 = 64;
+
+///message sent, but not yet acknowledged
+#[derive(Serialize, Deserialize, Clone)]
+struct UnackedMessage {
+    ///the message as it was sent
+    json: String,
+    ///time of the last send in milliseconds
+    last_sent_ms: f64,
+}
+
+///ordered and reliable stream of messages between 2 players.
+///It is saved with the game, so the sequence numbers continue after a reload.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReliableChannel<T> {
+    ///sequence number for the next message to send. It starts with 1.
+    next_outgoing_seq: u64,
+    ///messages waiting for acknowledgement
+    unacked_messages: BTreeMap<u64, UnackedMessage>,
+    ///sequence number of the next message to deliver to the game
+    next_incoming_seq: u64,
+    ///messages that came before the previous messages
+    incoming_buffer: BTreeMap<u64, T>,
+}
+//endregion
+
+impl<T> Default for ReliableChannel<T> {
+    fn default() -> Self {
+        ReliableChannel::new()
+    }
+}
+
+impl<T> ReliableChannel<T> {
+    ///new channel for a new game
+    pub fn new() -> Self {
+        ReliableChannel {
+            next_outgoing_seq: 1,
+            unacked_messages: BTreeMap::new(),
+            next_incoming_seq: 1,
+            incoming_buffer: BTreeMap::new(),
+        }
+    }
+    ///sequence number for the next message to send
+    pub fn next_seq(&mut self) -> u64 {
+        let seq = self.next_outgoing_seq;
+        self.next_outgoing_seq += 1;
+        seq
+    }
+    ///remember the sent message until it is acknowledged
+    pub fn sent(&mut self, seq: u64, json: String, now_ms: f64) {
+        self.unacked_messages.insert(
+            seq,
+            UnackedMessage {
+                json,
+                last_sent_ms: now_ms,
+            },
+        );
+    }
+    ///the other player received the message
+    pub fn ack(&mut self, seq: u64) {
+        self.unacked_messages.remove(&seq);
+    }
+    ///a message is received. Returns the messages that can be delivered to the game in the right order.
+    ///A duplicate message returns nothing, but it must be acknowledged again.
+    ///A message after the receive window returns None and it must not be acknowledged.
+    pub fn receive(&mut self, seq: u64, msg: T) -> Option<Vec<T>> {
+        if seq >= self.next_incoming_seq.saturating_add(RECEIVE_WINDOW) {
+            return None;
+        }
+        if seq >= self.next_incoming_seq {
+            self.incoming_buffer.entry(seq).or_insert(msg);
+        }
+        let mut vec_ready = Vec::new();
+        while let Some(msg) = self.incoming_buffer.remove(&self.next_incoming_seq) {
+            vec_ready.push(msg);
+            self.next_incoming_seq += 1;
+        }
+        Some(vec_ready)
+    }
+    ///all the messages that are not acknowledged. After a reconnect they are sent again immediately.
+    pub fn resend_all(&mut self, now_ms: f64) -> Vec<String> {
+        self.unacked_messages
+            .values_mut()
+            .map(|unacked_message| {
+                unacked_message.last_sent_ms = now_ms;
+                unacked_message.json.clone()
+            })
+            .collect()
+    }
+    ///the messages that waited too long for the acknowledgement. They must be sent again.
+    pub fn due_for_resend(&mut self, now_ms: f64) -> Vec<String> {
+        let mut vec_json = Vec::new();
+        for unacked_message in self.unacked_messages.values_mut() {
+            if now_ms - unacked_message.last_sent_ms >= RESEND_TIMEOUT_MS {
+                unacked_message.last_sent_ms = now_ms;
+                vec_json.push(unacked_message.json.clone());
+            }
+        }
+        vec_json
+    }
+}
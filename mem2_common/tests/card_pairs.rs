@@ -0,0 +1,121 @@
+//! A pack can pair 2 different faces with the same pair id, like a letter and a picture.
+//! The engine matches the 2 faces of a pair and not 2 different pairs.
+
+mod common;
+
+use mem2_common::gameengine::{GameAction, GameEngine};
+use mem2_common::{CardPair, GridSize, PackCards};
+
+///3 pairs of a letter and a word, and one card without a pair
+fn letters_and_words() -> PackCards {
+    serde_json::from_str(
+        r#"{"cards":[
+            {"image":"img/a.png","label":"A","pair_id":"a"},
+            {"image":"img/b.png","label":"B","pair_id":"b"},
+            {"image":"img/c.png","label":"C","pair_id":"c"},
+            {"image":"img/alpha.png","label":"alpha","pair_id":"a"},
+            {"image":"img/bravo.png","label":"bravo","pair_id":"b"},
+            {"image":"img/charlie.png","label":"charlie","pair_id":"c"},
+            {"image":"img/delta.png","label":"delta","pair_id":"d"}
+        ]}"#,
+    )
+    .expect("not PackCards")
+}
+
+///the deck with the pairs of different faces, player 1 in turn
+fn started_engine(vec_pairs: Vec<CardPair>) -> GameEngine {
+    common::started_engine_with_pairs(GridSize { rows: 2, cols: 3 }, vec_pairs)
+}
+
+///the card index of the card number
+fn card_index(game_engine: &GameEngine, card_number: usize) -> usize {
+    game_engine
+        .vec_cards
+        .iter()
+        .find(|card| card.card_number_and_img_src == card_number)
+        .expect("the card number is not in the deck")
+        .card_index_and_id
+}
+
+#[test]
+fn pairs_from_the_pair_ids() {
+    let pack_cards = letters_and_words();
+    assert_eq!(
+        pack_cards.pairs(),
+        vec![
+            CardPair {
+                first: 1,
+                second: 4
+            },
+            CardPair {
+                first: 2,
+                second: 5
+            },
+            CardPair {
+                first: 3,
+                second: 6
+            },
+        ]
+    );
+    //the pair id d has only one card
+    assert_eq!(pack_cards.pair_count(), 3);
+    assert!(CardPair {
+        first: 1,
+        second: 4
+    }
+    .matches(4, 1));
+    assert!(!CardPair {
+        first: 1,
+        second: 4
+    }
+    .matches(1, 1));
+}
+
+#[test]
+fn a_classic_pack_has_no_pairs() {
+    let pack_cards: PackCards = serde_json::from_str(
+        r#"{"cards":[{"image":"img/1.png","label":"one"},{"image":"img/2.png","label":"two"}]}"#,
+    )
+    .expect("not PackCards");
+    assert!(pack_cards.pairs().is_empty());
+    assert_eq!(pack_cards.pair_count(), 2);
+}
+
+#[test]
+fn the_deck_has_both_faces_of_every_pair() {
+    let game_engine = started_engine(letters_and_words().pairs());
+    let mut vec_card_numbers: Vec<usize> = game_engine
+        .vec_cards
+        .iter()
+        .skip(1)
+        .map(|card| card.card_number_and_img_src)
+        .collect();
+    vec_card_numbers.sort();
+    assert_eq!(vec_card_numbers, vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn the_2_faces_of_a_pair_match() {
+    let mut game_engine = started_engine(letters_and_words().pairs());
+    game_engine.apply(GameAction::Click {
+        card_index: card_index(&game_engine, 2),
+    });
+    game_engine.apply(GameAction::Click {
+        card_index: card_index(&game_engine, 5),
+    });
+    assert!(game_engine.is_pair_matched());
+    assert_eq!(game_engine.points(1), 1);
+}
+
+#[test]
+fn faces_of_different_pairs_do_not_match() {
+    let mut game_engine = started_engine(letters_and_words().pairs());
+    game_engine.apply(GameAction::Click {
+        card_index: card_index(&game_engine, 1),
+    });
+    game_engine.apply(GameAction::Click {
+        card_index: card_index(&game_engine, 5),
+    });
+    assert!(!game_engine.is_pair_matched());
+    assert_eq!(game_engine.points(1), 0);
+}
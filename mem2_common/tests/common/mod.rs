@@ -0,0 +1,102 @@
+//! The decks and the moves shared by the tests of the game engine.
+//! Every deck is built from the same seed, so the tests see always the same cards.
+//! Every test file uses only some of the helpers.
+#![allow(dead_code)]
+
+use mem2_common::gameengine::{DeckParameters, GameAction, GameEngine};
+use mem2_common::{CardPair, GridSize};
+
+///the seed of all the test decks
+pub const SEED: u64 = 42;
+///the different images of the classic content pack
+pub const CARD_IMAGES_COUNT: usize = 26;
+
+///the same deck every time. Nobody plays before the start.
+pub fn new_engine(grid_size: GridSize) -> GameEngine {
+    GameEngine::new(DeckParameters {
+        seed: SEED,
+        grid_size,
+        card_images_count: CARD_IMAGES_COUNT,
+    })
+    .expect("the grid is possible")
+}
+
+///the same deck every time for the count of players, player 1 in turn
+pub fn started_engine(grid_size: GridSize, player_count: usize) -> GameEngine {
+    let mut game_engine = new_engine(grid_size);
+    game_engine.set_player_count(player_count);
+    game_engine.start();
+    game_engine
+}
+
+///the same deck of pairs of different faces every time, player 1 in turn
+pub fn started_engine_with_pairs(grid_size: GridSize, vec_pairs: Vec<CardPair>) -> GameEngine {
+    let mut game_engine = GameEngine::new_with_pairs(
+        DeckParameters {
+            seed: SEED,
+            grid_size,
+            card_images_count: vec_pairs.len(),
+        },
+        vec_pairs,
+    )
+    .expect("the grid is possible");
+    game_engine.start();
+    game_engine
+}
+
+///the card index of the other card of the pair
+pub fn other_of_pair(game_engine: &GameEngine, card_index: usize) -> usize {
+    let card_number = game_engine.vec_cards[card_index].card_number_and_img_src;
+    game_engine
+        .vec_cards
+        .iter()
+        .skip(1)
+        .find(|card| {
+            card.card_index_and_id != card_index
+                && game_engine.is_pair(card_number, card.card_number_and_img_src)
+        })
+        .expect("no pair")
+        .card_index_and_id
+}
+
+///the first card face down and the other card of its pair
+pub fn face_down_pair(game_engine: &GameEngine) -> (usize, usize) {
+    let first = (1..game_engine.vec_cards.len())
+        .find(|card_index| {
+            game_engine
+                .validate_click(game_engine.player_turn, *card_index)
+                .is_ok()
+        })
+        .expect("no card face down");
+    (first, other_of_pair(game_engine, first))
+}
+
+///2 cards face down that are not a pair
+pub fn not_matching_pair(game_engine: &GameEngine) -> (usize, usize) {
+    let (first, second) = face_down_pair(game_engine);
+    let third = (1..game_engine.vec_cards.len())
+        .find(|card_index| {
+            *card_index != first
+                && *card_index != second
+                && game_engine
+                    .validate_click(game_engine.player_turn, *card_index)
+                    .is_ok()
+        })
+        .expect("no other card face down");
+    (first, third)
+}
+
+///the player in turn finds a pair and receives the point
+pub fn find_pair(game_engine: &mut GameEngine) {
+    let (first, second) = face_down_pair(game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+}
+
+///the player in turn opens 2 cards that are not a pair and the next player takes the turn
+pub fn miss(game_engine: &mut GameEngine) {
+    let (first, second) = not_matching_pair(game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    game_engine.apply(GameAction::TakeTurn);
+}
@@ -0,0 +1,112 @@
+//! The computer opponent clicks only the cards it may click
+//! and uses the pairs it remembers.
+
+mod common;
+
+use common::other_of_pair;
+use mem2_common::computerplayer::{ComputerPlayer, MemoryStrength};
+use mem2_common::gameengine::{GameAction, GameEngine, GameEvent};
+use mem2_common::{CardStatusCardFace, GridSize};
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+
+///the same deck every time, player 1 in turn
+fn started_engine() -> GameEngine {
+    common::started_engine(GridSize { rows: 4, cols: 4 }, 2)
+}
+
+///the card indexes of the card 1 and of the other card of its pair
+fn first_pair(game_engine: &GameEngine) -> (usize, usize) {
+    (1, other_of_pair(game_engine, 1))
+}
+
+#[test]
+fn perfect_memory_clicks_a_known_pair() {
+    let game_engine = started_engine();
+    let mut rng = Pcg32::seed_from_u64(1);
+    let mut computer_player = ComputerPlayer::new(MemoryStrength::Perfect);
+    let (first, second) = first_pair(&game_engine);
+    computer_player.observe(&game_engine, second, &mut rng);
+    computer_player.observe(&game_engine, first, &mut rng);
+    assert_eq!(computer_player.remembered_count(), 2);
+    let card_index = computer_player
+        .choose_click(&game_engine, &mut rng)
+        .expect("no click");
+    assert!(card_index == first || card_index == second);
+}
+
+#[test]
+fn the_second_click_is_the_other_face_if_known() {
+    let mut game_engine = started_engine();
+    let mut rng = Pcg32::seed_from_u64(2);
+    let mut computer_player = ComputerPlayer::new(MemoryStrength::Perfect);
+    let (first, second) = first_pair(&game_engine);
+    computer_player.observe(&game_engine, second, &mut rng);
+    game_engine.apply(GameAction::Click { card_index: first });
+    assert_eq!(
+        computer_player.choose_click(&game_engine, &mut rng),
+        Some(second)
+    );
+}
+
+#[test]
+fn easy_memory_forgets_the_oldest_cards() {
+    let game_engine = started_engine();
+    let mut rng = Pcg32::seed_from_u64(3);
+    let mut computer_player = ComputerPlayer::new(MemoryStrength::Easy);
+    for _ in 0..10 {
+        for card_index in 1..17 {
+            computer_player.observe(&game_engine, card_index, &mut rng);
+        }
+    }
+    assert_eq!(
+        computer_player.remembered_count(),
+        MemoryStrength::Easy.capacity()
+    );
+    //the card back is never remembered
+    let mut computer_player = ComputerPlayer::new(MemoryStrength::Perfect);
+    computer_player.observe(&game_engine, 0, &mut rng);
+    assert_eq!(computer_player.remembered_count(), 0);
+}
+
+#[test]
+fn the_computer_finishes_a_game_against_itself() {
+    for memory_strength in [
+        MemoryStrength::Easy,
+        MemoryStrength::Medium,
+        MemoryStrength::Perfect,
+    ]
+    .iter()
+    {
+        let mut game_engine = started_engine();
+        let mut rng = Pcg32::seed_from_u64(4);
+        let mut computer_player = ComputerPlayer::new(*memory_strength);
+        let mut count_moves = 0;
+        while !game_engine.is_game_over() {
+            count_moves += 1;
+            assert!(count_moves < 1000, "the game does not end");
+            match computer_player.choose_click(&game_engine, &mut rng) {
+                Some(card_index) => {
+                    assert_eq!(
+                        game_engine.vec_cards[card_index].status,
+                        CardStatusCardFace::Down
+                    );
+                    let player = game_engine.player_turn;
+                    assert_eq!(game_engine.validate_click(player, card_index), Ok(()));
+                    for game_event in game_engine.apply(GameAction::Click { card_index }) {
+                        if let GameEvent::CardRevealed { card_index } = game_event {
+                            computer_player.observe(&game_engine, card_index, &mut rng);
+                        }
+                    }
+                }
+                None => {
+                    game_engine.apply(GameAction::TakeTurn);
+                }
+            }
+        }
+        assert_eq!(
+            game_engine.vec_points.iter().sum::<usize>(),
+            game_engine.grid_size.pair_count()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! The deck is built only from the deck parameters. The same seed always builds the same deck,
+//! so a game can be reproduced for bug reports. A grid that is not possible is an error and never a panic.
+
+use mem2_common::gameengine::{DeckError, DeckParameters, GameEngine};
+use mem2_common::GridSize;
+
+///the card numbers of the deck in the order of the grid
+fn card_numbers(deck_parameters: DeckParameters) -> Vec<usize> {
+    GameEngine::new(deck_parameters)
+        .expect("the grid is possible")
+        .vec_cards
+        .iter()
+        .map(|card| card.card_number_and_img_src)
+        .collect()
+}
+
+#[test]
+fn the_same_seed_builds_the_same_deck() {
+    let deck_parameters = DeckParameters {
+        seed: 42,
+        grid_size: GridSize { rows: 4, cols: 4 },
+        card_images_count: 26,
+    };
+    let vec_card_numbers = card_numbers(deck_parameters);
+    assert_eq!(vec_card_numbers.len(), 17);
+    assert_eq!(card_numbers(deck_parameters), vec_card_numbers);
+    let other_seed = DeckParameters {
+        seed: 43,
+        ..deck_parameters
+    };
+    assert_ne!(card_numbers(other_seed), vec_card_numbers);
+}
+
+#[test]
+fn every_card_has_a_pair() {
+    let vec_card_numbers = card_numbers(DeckParameters {
+        seed: 7,
+        grid_size: GridSize { rows: 3, cols: 4 },
+        card_images_count: 6,
+    });
+    for card_number in 1..=6 {
+        let count = vec_card_numbers
+            .iter()
+            .filter(|number| **number == card_number)
+            .count();
+        assert_eq!(count, 2);
+    }
+}
+
+#[test]
+fn a_grid_that_is_not_possible_is_an_error() {
+    for (grid_size, card_images_count) in [
+        //more pairs than different images
+        (GridSize { rows: 4, cols: 4 }, 7),
+        //no images at all
+        (GridSize { rows: 2, cols: 3 }, 0),
+        //an odd count of cards
+        (GridSize { rows: 3, cols: 3 }, 26),
+    ]
+    .iter()
+    {
+        let result = GameEngine::new(DeckParameters {
+            seed: 42,
+            grid_size: *grid_size,
+            card_images_count: *card_images_count,
+        });
+        assert_eq!(
+            result.err(),
+            Some(DeckError::GridNotPossible {
+                grid_size: *grid_size,
+                card_images_count: *card_images_count,
+            })
+        );
+    }
+}
+
+//the random generator draws u32 numbers, a bigger usize exists only on 64 bit
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn too_many_images_for_the_random_generator_are_an_error() {
+    let card_images_count = usize::MAX;
+    let result = GameEngine::new(DeckParameters {
+        seed: 42,
+        grid_size: GridSize { rows: 2, cols: 3 },
+        card_images_count,
+    });
+    assert_eq!(
+        result.err(),
+        Some(DeckError::TooManyImages { card_images_count })
+    );
+}
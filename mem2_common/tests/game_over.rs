@@ -0,0 +1,64 @@
+//! The game is over when all the pairs are found. The most points win, equal points are a draw.
+//! The rematch starts with the player after the one that started the game before.
+
+mod common;
+
+use common::{find_pair, miss, started_engine};
+use mem2_common::gameengine::GameResult;
+use mem2_common::GridSize;
+
+#[test]
+fn the_most_points_win() {
+    let mut game_engine = started_engine(GridSize { rows: 2, cols: 3 }, 2);
+    find_pair(&mut game_engine);
+    assert_eq!(game_engine.game_result(), None);
+    miss(&mut game_engine);
+    find_pair(&mut game_engine);
+    find_pair(&mut game_engine);
+    assert!(game_engine.is_game_over());
+    assert_eq!(
+        game_engine.game_result(),
+        Some(GameResult::Winner {
+            player: 2,
+            points: 2
+        })
+    );
+}
+
+#[test]
+fn equal_points_are_a_draw() {
+    let mut game_engine = started_engine(GridSize { rows: 3, cols: 4 }, 2);
+    for _ in 0..3 {
+        find_pair(&mut game_engine);
+    }
+    miss(&mut game_engine);
+    for _ in 0..3 {
+        find_pair(&mut game_engine);
+    }
+    assert_eq!(game_engine.vec_points, vec![3, 3]);
+    assert_eq!(
+        game_engine.game_result(),
+        Some(GameResult::Draw {
+            vec_players: vec![1, 2],
+            points: 3
+        })
+    );
+}
+
+#[test]
+fn the_rematch_swaps_who_starts() {
+    let grid_size = GridSize { rows: 2, cols: 3 };
+    let game_engine = started_engine(grid_size, 2);
+    assert_eq!(game_engine.starting_player, 1);
+    assert_eq!(game_engine.next_starting_player(), 2);
+    let mut rematch_engine = started_engine(grid_size, 2);
+    rematch_engine.start_with_player(game_engine.next_starting_player());
+    assert_eq!(rematch_engine.player_turn, 2);
+    assert_eq!(rematch_engine.next_starting_player(), 1);
+    let mut game_engine = started_engine(grid_size, 3);
+    game_engine.start_with_player(3);
+    assert_eq!(game_engine.next_starting_player(), 1);
+    //a player that is not in the game does not start
+    game_engine.start_with_player(7);
+    assert_eq!(game_engine.player_turn, 1);
+}
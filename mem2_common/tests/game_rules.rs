@@ -0,0 +1,90 @@
+//! The basic rules of the game: 2 cards of a pair stay face up and give a point to the player,
+//! 2 cards that are not a pair are flipped back when the next player takes the turn.
+
+mod common;
+
+use common::{face_down_pair, not_matching_pair};
+use mem2_common::gameengine::{GameAction, GameEngine, GameEvent};
+use mem2_common::{CardStatusCardFace, GridSize};
+
+///the same deck every time for 2 players, player 1 in turn
+fn started_engine() -> GameEngine {
+    common::started_engine(GridSize { rows: 3, cols: 4 }, 2)
+}
+
+#[test]
+fn a_pair_stays_face_up() {
+    let mut game_engine = started_engine();
+    let (first, second) = face_down_pair(&game_engine);
+    let vec_events = game_engine.apply(GameAction::Click { card_index: first });
+    assert!(vec_events.contains(&GameEvent::CardFlipped {
+        card_index: first,
+        count_click_inside_one_turn: 1
+    }));
+    let vec_events = game_engine.apply(GameAction::Click { card_index: second });
+    assert!(vec_events.contains(&GameEvent::PairMatched { player: 1 }));
+    assert!(game_engine.is_pair_matched());
+    for card_index in [first, second].iter() {
+        assert_eq!(
+            game_engine.vec_cards[*card_index].status,
+            CardStatusCardFace::UpPermanently
+        );
+    }
+}
+
+#[test]
+fn not_a_pair_is_flipped_back_on_the_turn_change() {
+    let mut game_engine = started_engine();
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    let vec_events = game_engine.apply(GameAction::Click { card_index: second });
+    assert!(vec_events.contains(&GameEvent::PairNotMatched));
+    assert_eq!(
+        game_engine.vec_cards[second].status,
+        CardStatusCardFace::UpTemporary
+    );
+    assert_eq!(
+        game_engine.apply(GameAction::TakeTurn),
+        vec![GameEvent::TurnChanged { player_turn: 2 }]
+    );
+    for card_index in [first, second].iter() {
+        assert_eq!(
+            game_engine.vec_cards[*card_index].status,
+            CardStatusCardFace::Down
+        );
+    }
+    assert_eq!(game_engine.count_click_inside_one_turn, 0);
+}
+
+#[test]
+fn the_player_keeps_the_turn_after_a_pair() {
+    let mut game_engine = started_engine();
+    let (first, second) = face_down_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(game_engine.player_turn, 1);
+    //the turn can be taken only after 2 cards that are not a pair
+    assert!(game_engine.apply(GameAction::TakeTurn).is_empty());
+    assert_eq!(game_engine.player_turn, 1);
+    let (third, _) = face_down_pair(&game_engine);
+    assert_eq!(game_engine.validate_click(1, third), Ok(()));
+}
+
+#[test]
+fn every_pair_is_a_point_for_the_player_in_turn() {
+    let mut game_engine = started_engine();
+    let (first, second) = face_down_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(game_engine.vec_points, vec![1, 0]);
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(game_engine.vec_points, vec![1, 0]);
+    game_engine.apply(GameAction::TakeTurn);
+    let (first, second) = face_down_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(game_engine.vec_points, vec![1, 1]);
+    assert_eq!(game_engine.count_all_clicks, 6);
+}
@@ -0,0 +1,65 @@
+//! The manifest `content/packs.json` that is served with the game must be parsed by the client.
+
+use mem2_common::{ContentPack, PackCards, PacksManifest, CARD_IMAGES_COUNT, GRID_SIZES};
+use std::path::PathBuf;
+
+///the manifest from the web root of this repository
+fn packs_manifest() -> PacksManifest {
+    serde_json::from_str(include_str!("../../content/packs.json"))
+        .expect("content/packs.json is not a PacksManifest")
+}
+
+///the folder of the pack in the web root of this repository
+fn pack_folder(content_pack: &ContentPack) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(&content_pack.folder)
+}
+
+#[test]
+fn the_default_pack_is_in_the_manifest() {
+    let packs_manifest = packs_manifest();
+    assert_eq!(
+        packs_manifest.find("content"),
+        Some(&ContentPack::default())
+    );
+    assert_eq!(ContentPack::default().card_count, CARD_IMAGES_COUNT);
+}
+
+#[test]
+fn every_pack_has_a_folder_and_enough_cards() {
+    let packs_manifest = packs_manifest();
+    assert!(packs_manifest.packs.len() >= 2);
+    for content_pack in &packs_manifest.packs {
+        assert!(content_pack.folder.starts_with("content"));
+        assert!(!content_pack.name.is_empty());
+        assert!(GRID_SIZES
+            .iter()
+            .any(|grid_size| grid_size.is_possible(content_pack.card_count)));
+    }
+    assert!(packs_manifest.find("content02").is_some());
+    assert!(packs_manifest.find("content99").is_none());
+}
+
+#[test]
+fn every_pack_has_metadata_for_every_card() {
+    for content_pack in &packs_manifest().packs {
+        let folder = pack_folder(content_pack);
+        assert!(folder.join(&content_pack.card_back).is_file());
+        let text = std::fs::read_to_string(folder.join(&content_pack.cards_file))
+            .expect("the cards file cannot be read");
+        let pack_cards: PackCards = serde_json::from_str(&text).expect("not PackCards");
+        assert_eq!(pack_cards.cards.len(), content_pack.card_count);
+        //the card number 0 is the card back
+        assert!(pack_cards.card(0).is_none());
+        assert_eq!(pack_cards.card(1), pack_cards.cards.first());
+        assert!(pack_cards.card(content_pack.card_count + 1).is_none());
+        for card_metadata in &pack_cards.cards {
+            assert!(!card_metadata.label.is_empty());
+            assert!(folder.join(&card_metadata.image).is_file());
+            if let Some(sound) = &card_metadata.sound {
+                assert!(folder.join(sound).is_file());
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! From 3 to 6 players take turns in the order of their player numbers.
+//! Every player has own points. Only the next player can take the turn.
+
+mod common;
+
+use common::{not_matching_pair, other_of_pair};
+use mem2_common::gameengine::{GameAction, GameEngine, RuleViolation};
+use mem2_common::{GridSize, MAX_PLAYERS};
+
+///the same deck every time for the count of players
+fn started_engine(player_count: usize) -> GameEngine {
+    common::started_engine(GridSize { rows: 3, cols: 4 }, player_count)
+}
+
+///the player in turn flips 2 cards that do not match and the next player takes the turn
+fn pass_the_turn(game_engine: &mut GameEngine) {
+    let (first, second) = not_matching_pair(game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    let next_player = game_engine.next_player();
+    assert_eq!(game_engine.validate_take_turn(next_player), Ok(()));
+    game_engine.apply(GameAction::TakeTurn);
+}
+
+#[test]
+fn the_turn_goes_round_robin() {
+    let mut game_engine = started_engine(4);
+    assert_eq!(game_engine.vec_points, vec![0; 4]);
+    for expected_player in [2, 3, 4, 1, 2].iter() {
+        pass_the_turn(&mut game_engine);
+        assert_eq!(game_engine.player_turn, *expected_player);
+    }
+}
+
+#[test]
+fn only_the_next_player_takes_the_turn() {
+    let mut game_engine = started_engine(MAX_PLAYERS);
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    for player in [1, 3, MAX_PLAYERS, MAX_PLAYERS + 1].iter() {
+        assert_eq!(
+            game_engine.validate_take_turn(*player),
+            Err(RuleViolation::NotYourTurn { player: *player })
+        );
+    }
+    assert_eq!(game_engine.validate_take_turn(2), Ok(()));
+}
+
+#[test]
+fn every_player_has_own_points() {
+    let mut game_engine = started_engine(3);
+    pass_the_turn(&mut game_engine);
+    pass_the_turn(&mut game_engine);
+    assert_eq!(game_engine.player_turn, 3);
+    let first = (1..game_engine.vec_cards.len())
+        .find(|card_index| {
+            game_engine.validate_click(3, *card_index).is_ok()
+                && game_engine
+                    .validate_click(3, other_of_pair(&game_engine, *card_index))
+                    .is_ok()
+        })
+        .expect("no face down pair");
+    let second = other_of_pair(&game_engine, first);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(game_engine.vec_points, vec![0, 0, 1]);
+    assert_eq!(game_engine.points(3), 1);
+    assert_eq!(game_engine.points(4), 0);
+    assert_eq!(game_engine.points(0), 0);
+}
+
+#[test]
+fn the_snapshot_has_the_count_of_players() {
+    let mut game_engine = started_engine(5);
+    pass_the_turn(&mut game_engine);
+    let game_snapshot = game_engine
+        .snapshot()
+        .expect("the deck owner has a snapshot");
+    assert_eq!(game_snapshot.vec_points.len(), 5);
+    let mut other_engine = GameEngine::from_commitments(
+        game_engine.grid_size,
+        game_engine.vec_card_commitments.clone(),
+    );
+    assert_eq!(other_engine.player_count, 2);
+    assert!(other_engine.restore_snapshot(&game_snapshot));
+    assert_eq!(other_engine.player_count, 5);
+    assert_eq!(other_engine.player_turn, 2);
+    assert_eq!(other_engine.state_hash(), game_engine.state_hash());
+}
@@ -0,0 +1,130 @@
+//! The game gets the messages of the other player only once and in the order they were sent.
+//! The receiver keeps only the messages inside the receive window.
+//! The sender keeps every message until the acknowledgement and resends it after the timeout.
+
+use mem2_common::reliablechannel::{ReliableChannel, RECEIVE_WINDOW, RESEND_TIMEOUT_MS};
+
+///the sender with 3 messages sent at the time 0
+fn sender_with_3_messages() -> ReliableChannel<&'static str> {
+    let mut reliable_channel = ReliableChannel::new();
+    for json in ["first", "second", "third"].iter() {
+        let seq = reliable_channel.next_seq();
+        reliable_channel.sent(seq, json.to_string(), 0.0);
+    }
+    reliable_channel
+}
+
+#[test]
+fn the_sequence_numbers_start_with_1() {
+    let mut reliable_channel: ReliableChannel<&str> = ReliableChannel::new();
+    assert_eq!(reliable_channel.next_seq(), 1);
+    assert_eq!(reliable_channel.next_seq(), 2);
+}
+
+#[test]
+fn the_messages_in_order_are_delivered_at_once() {
+    let mut reliable_channel = ReliableChannel::new();
+    assert_eq!(reliable_channel.receive(1, "first"), Some(vec!["first"]));
+    assert_eq!(reliable_channel.receive(2, "second"), Some(vec!["second"]));
+}
+
+#[test]
+fn the_messages_out_of_order_wait_for_the_missing_one() {
+    let mut reliable_channel = ReliableChannel::new();
+    assert_eq!(reliable_channel.receive(3, "third"), Some(Vec::new()));
+    assert_eq!(reliable_channel.receive(2, "second"), Some(Vec::new()));
+    assert_eq!(
+        reliable_channel.receive(1, "first"),
+        Some(vec!["first", "second", "third"])
+    );
+    assert_eq!(reliable_channel.receive(4, "fourth"), Some(vec!["fourth"]));
+}
+
+#[test]
+fn the_duplicates_are_dropped() {
+    let mut reliable_channel = ReliableChannel::new();
+    assert_eq!(reliable_channel.receive(1, "first"), Some(vec!["first"]));
+    assert_eq!(reliable_channel.receive(1, "first again"), Some(Vec::new()));
+    //a duplicate that waits in the buffer does not replace the first one
+    assert_eq!(reliable_channel.receive(3, "third"), Some(Vec::new()));
+    assert_eq!(reliable_channel.receive(3, "third again"), Some(Vec::new()));
+    assert_eq!(
+        reliable_channel.receive(2, "second"),
+        Some(vec!["second", "third"])
+    );
+}
+
+#[test]
+fn the_messages_after_the_window_are_dropped_without_ack() {
+    let mut reliable_channel = ReliableChannel::new();
+    //the last message that fits in the window waits for the missing ones
+    assert_eq!(
+        reliable_channel.receive(RECEIVE_WINDOW, "last"),
+        Some(Vec::new())
+    );
+    assert_eq!(reliable_channel.receive(RECEIVE_WINDOW + 1, "after"), None);
+    assert_eq!(reliable_channel.receive(u64::MAX, "far after"), None);
+    //the window moves with the delivered messages
+    for seq in 1..RECEIVE_WINDOW - 1 {
+        assert_eq!(
+            reliable_channel.receive(seq, "middle"),
+            Some(vec!["middle"])
+        );
+    }
+    assert_eq!(
+        reliable_channel.receive(RECEIVE_WINDOW - 1, "before last"),
+        Some(vec!["before last", "last"])
+    );
+    assert_eq!(
+        reliable_channel.receive(RECEIVE_WINDOW + 1, "after"),
+        Some(vec!["after"])
+    );
+}
+
+#[test]
+fn the_ack_removes_the_message_from_the_resend() {
+    let mut reliable_channel = sender_with_3_messages();
+    reliable_channel.ack(2);
+    //an unknown or repeated ack changes nothing
+    reliable_channel.ack(2);
+    reliable_channel.ack(99);
+    assert_eq!(
+        reliable_channel.resend_all(0.0),
+        vec!["first".to_string(), "third".to_string()]
+    );
+    reliable_channel.ack(1);
+    reliable_channel.ack(3);
+    assert!(reliable_channel.resend_all(0.0).is_empty());
+}
+
+#[test]
+fn the_message_is_resent_after_the_timeout() {
+    let mut reliable_channel = sender_with_3_messages();
+    reliable_channel.ack(1);
+    assert!(reliable_channel
+        .due_for_resend(RESEND_TIMEOUT_MS - 1.0)
+        .is_empty());
+    assert_eq!(
+        reliable_channel.due_for_resend(RESEND_TIMEOUT_MS),
+        vec!["second".to_string(), "third".to_string()]
+    );
+    //the timeout starts again with the resend
+    assert!(reliable_channel
+        .due_for_resend(RESEND_TIMEOUT_MS + 1.0)
+        .is_empty());
+    assert_eq!(
+        reliable_channel
+            .due_for_resend(2.0 * RESEND_TIMEOUT_MS)
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn resend_all_restarts_the_timeout() {
+    let mut reliable_channel = sender_with_3_messages();
+    assert_eq!(reliable_channel.resend_all(RESEND_TIMEOUT_MS).len(), 3);
+    assert!(reliable_channel
+        .due_for_resend(RESEND_TIMEOUT_MS + 1.0)
+        .is_empty());
+}
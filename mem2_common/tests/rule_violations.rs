@@ -0,0 +1,109 @@
+//! The moves of the other player are validated before they are applied.
+//! An illegal move must be a `RuleViolation` and never a panic.
+
+mod common;
+
+use common::{new_engine, not_matching_pair};
+use mem2_common::gameengine::{GameAction, GameEngine, RuleViolation};
+use mem2_common::{CardStatusCardFace, GridSize};
+
+///the same deck every time, player 1 in turn
+fn started_engine() -> GameEngine {
+    common::started_engine(GridSize { rows: 2, cols: 3 }, 2)
+}
+
+#[test]
+fn card_index_out_of_range() {
+    let game_engine = started_engine();
+    assert_eq!(
+        game_engine.validate_click(1, 0),
+        Err(RuleViolation::CardIndexOutOfRange { card_index: 0 })
+    );
+    assert_eq!(
+        game_engine.validate_click(1, 7),
+        Err(RuleViolation::CardIndexOutOfRange { card_index: 7 })
+    );
+    assert_eq!(
+        game_engine.validate_click(1, usize::MAX),
+        Err(RuleViolation::CardIndexOutOfRange {
+            card_index: usize::MAX
+        })
+    );
+    assert_eq!(game_engine.validate_click(1, 6), Ok(()));
+}
+
+#[test]
+fn click_out_of_turn() {
+    let game_engine = started_engine();
+    assert_eq!(
+        game_engine.validate_click(2, 1),
+        Err(RuleViolation::NotYourTurn { player: 2 })
+    );
+    let game_engine = new_engine(GridSize { rows: 2, cols: 3 });
+    //nobody plays before the start
+    assert_eq!(
+        game_engine.validate_click(1, 1),
+        Err(RuleViolation::NotYourTurn { player: 1 })
+    );
+}
+
+#[test]
+fn click_on_face_up_card() {
+    let mut game_engine = started_engine();
+    game_engine.apply(GameAction::Click { card_index: 3 });
+    assert_eq!(
+        game_engine.vec_cards[3].status,
+        CardStatusCardFace::UpTemporary
+    );
+    assert_eq!(
+        game_engine.validate_click(1, 3),
+        Err(RuleViolation::CardAlreadyFaceUp { card_index: 3 })
+    );
+}
+
+#[test]
+fn click_count_sequence() {
+    let mut game_engine = started_engine();
+    assert_eq!(
+        game_engine.validate_remote_click(1, 1, 2),
+        Err(RuleViolation::ClickCountMismatch {
+            expected: 1,
+            received: 2
+        })
+    );
+    assert_eq!(game_engine.validate_remote_click(1, 1, 1), Ok(()));
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    let third = (1..7)
+        .find(|card_index| *card_index != first && *card_index != second)
+        .expect("no third card");
+    assert_eq!(
+        game_engine.validate_remote_click(1, third, 3),
+        Err(RuleViolation::ThirdClick)
+    );
+}
+
+#[test]
+fn take_turn_only_after_a_pair_that_did_not_match() {
+    let mut game_engine = started_engine();
+    assert_eq!(
+        game_engine.validate_take_turn(2),
+        Err(RuleViolation::TurnNotOver)
+    );
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    assert_eq!(
+        game_engine.validate_take_turn(1),
+        Err(RuleViolation::NotYourTurn { player: 1 })
+    );
+    assert_eq!(
+        game_engine.validate_take_turn(3),
+        Err(RuleViolation::NotYourTurn { player: 3 })
+    );
+    assert_eq!(game_engine.validate_take_turn(2), Ok(()));
+    game_engine.apply(GameAction::TakeTurn);
+    assert_eq!(game_engine.player_turn, 2);
+    assert_eq!(game_engine.validate_click(2, first), Ok(()));
+}
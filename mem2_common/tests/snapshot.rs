@@ -0,0 +1,100 @@
+//! The snapshot of the deck owner replaces the game state of the other player.
+//! A snapshot that does not fit the deck changes nothing: every card face up needs its verified reveal,
+//! the player in turn must be in the game and the clicked cards must be in the grid.
+
+mod common;
+
+use common::{find_pair, not_matching_pair};
+use mem2_common::gameengine::{GameAction, GameEngine};
+use mem2_common::{CardStatusCardFace, GameSnapshot, GridSize};
+
+///the deck owner after a pair and 2 cards that are not a pair
+fn deck_owner() -> GameEngine {
+    let mut game_engine = common::started_engine(GridSize { rows: 3, cols: 4 }, 2);
+    find_pair(&mut game_engine);
+    let (first, second) = not_matching_pair(&game_engine);
+    game_engine.apply(GameAction::Click { card_index: first });
+    game_engine.apply(GameAction::Click { card_index: second });
+    game_engine
+}
+
+///the other player knows only the commitments of the deck owner
+fn other_player(deck_owner: &GameEngine) -> GameEngine {
+    let mut game_engine = GameEngine::from_commitments(
+        deck_owner.grid_size,
+        deck_owner.vec_card_commitments.clone(),
+    );
+    game_engine.start();
+    game_engine
+}
+
+///the snapshot of the deck owner
+fn owner_snapshot(deck_owner: &GameEngine) -> GameSnapshot {
+    deck_owner
+        .snapshot()
+        .expect("the deck owner has a snapshot")
+}
+
+///the snapshot is rejected and the game state of the other player does not change
+fn assert_rejected(deck_owner: &GameEngine, game_snapshot: &GameSnapshot) {
+    let mut game_engine = other_player(deck_owner);
+    let state_hash = game_engine.state_hash();
+    assert!(!game_engine.restore_snapshot(game_snapshot));
+    assert_eq!(game_engine.state_hash(), state_hash);
+    assert_eq!(game_engine.vec_points, vec![0, 0]);
+}
+
+#[test]
+fn the_snapshot_of_the_deck_owner_is_restored() {
+    let deck_owner = deck_owner();
+    let mut game_engine = other_player(&deck_owner);
+    assert!(game_engine.restore_snapshot(&owner_snapshot(&deck_owner)));
+    assert_eq!(game_engine.state_hash(), deck_owner.state_hash());
+    assert_eq!(game_engine.vec_points, vec![1, 0]);
+}
+
+#[test]
+fn a_card_face_up_without_reveal_is_rejected() {
+    let deck_owner = deck_owner();
+    //all the cards face up and all the points for the other player
+    let mut game_snapshot = owner_snapshot(&deck_owner);
+    for status in game_snapshot.vec_card_statuses.iter_mut().skip(1) {
+        *status = CardStatusCardFace::UpPermanently;
+    }
+    game_snapshot.vec_points = vec![0, 6];
+    assert_rejected(&deck_owner, &game_snapshot);
+    //one reveal less is enough
+    let mut game_snapshot = owner_snapshot(&deck_owner);
+    game_snapshot.vec_card_reveals.pop();
+    assert_rejected(&deck_owner, &game_snapshot);
+}
+
+#[test]
+fn a_reveal_that_does_not_match_the_commitment_is_rejected() {
+    let deck_owner = deck_owner();
+    let mut game_snapshot = owner_snapshot(&deck_owner);
+    game_snapshot.vec_card_reveals[0].card_number_and_img_src += 1;
+    assert_rejected(&deck_owner, &game_snapshot);
+}
+
+#[test]
+fn a_player_turn_out_of_the_game_is_rejected() {
+    let deck_owner = deck_owner();
+    for player_turn in [0, 3, usize::MAX].iter() {
+        let mut game_snapshot = owner_snapshot(&deck_owner);
+        game_snapshot.player_turn = *player_turn;
+        assert_rejected(&deck_owner, &game_snapshot);
+    }
+}
+
+#[test]
+fn a_click_out_of_the_grid_is_rejected() {
+    let deck_owner = deck_owner();
+    let card_count = deck_owner.vec_cards.len();
+    let mut game_snapshot = owner_snapshot(&deck_owner);
+    game_snapshot.card_index_of_first_click = card_count;
+    assert_rejected(&deck_owner, &game_snapshot);
+    let mut game_snapshot = owner_snapshot(&deck_owner);
+    game_snapshot.card_index_of_second_click = usize::MAX;
+    assert_rejected(&deck_owner, &game_snapshot);
+}
@@ -0,0 +1,54 @@
+//! In the solo mode one player flips all the cards.
+//! After 2 cards that did not match the same player takes the turn again.
+
+mod common;
+
+use common::other_of_pair;
+use mem2_common::gameengine::{GameAction, GameEngine, GameEvent};
+use mem2_common::{CardStatusCardFace, GridSize};
+
+///the same deck every time for one player
+fn solo_engine() -> GameEngine {
+    common::started_engine(GridSize { rows: 2, cols: 3 }, 1)
+}
+
+#[test]
+fn the_solo_player_keeps_the_turn() {
+    let mut game_engine = solo_engine();
+    let first = 1;
+    let second = (2..7)
+        .find(|card_index| *card_index != other_of_pair(&game_engine, first))
+        .expect("no other card");
+    game_engine.apply(GameAction::Click { card_index: first });
+    let vec_events = game_engine.apply(GameAction::Click { card_index: second });
+    assert!(vec_events.contains(&GameEvent::PairNotMatched));
+    assert_eq!(
+        game_engine.apply(GameAction::TakeTurn),
+        vec![GameEvent::TurnChanged { player_turn: 1 }]
+    );
+    assert_eq!(
+        game_engine.vec_cards[first].status,
+        CardStatusCardFace::Down
+    );
+    assert_eq!(
+        game_engine.vec_cards[second].status,
+        CardStatusCardFace::Down
+    );
+    assert_eq!(game_engine.count_click_inside_one_turn, 0);
+}
+
+#[test]
+fn the_game_is_over_when_all_pairs_are_found() {
+    let mut game_engine = solo_engine();
+    assert!(!game_engine.is_game_over());
+    for card_index in 1..7 {
+        if game_engine.vec_cards[card_index].status == CardStatusCardFace::Down {
+            let other = other_of_pair(&game_engine, card_index);
+            game_engine.apply(GameAction::Click { card_index });
+            game_engine.apply(GameAction::Click { card_index: other });
+        }
+    }
+    assert!(game_engine.is_game_over());
+    assert_eq!(game_engine.points(1), 3);
+    assert_eq!(game_engine.count_all_clicks, 6);
+}
@@ -0,0 +1,258 @@
+//! The json of the WebSocket protocol must not change by accident.
+//! Every fixture is a message as it goes over the WebSocket.
+//! It must be parsed and serialized back to the same json.
+
+use mem2_common::{
+    Card, CardReveal, CardStatusCardFace, GridSize, RoomPlayer, WsMessage, GRID_SIZES, MAX_PLAYERS,
+};
+use serde_json::Value;
+
+///all the fixtures with their file names for the error messages
+const FIXTURES: [(&str, &str); 21] = [
+    (
+        "connection_test",
+        include_str!("fixtures/connection_test.json"),
+    ),
+    ("hello", include_str!("fixtures/hello.json")),
+    ("want_to_play", include_str!("fixtures/want_to_play.json")),
+    ("accept_play", include_str!("fixtures/accept_play.json")),
+    ("player_click", include_str!("fixtures/player_click.json")),
+    (
+        "player_click_hidden",
+        include_str!("fixtures/player_click_hidden.json"),
+    ),
+    ("card_reveal", include_str!("fixtures/card_reveal.json")),
+    ("player_change", include_str!("fixtures/player_change.json")),
+    (
+        "resync_request",
+        include_str!("fixtures/resync_request.json"),
+    ),
+    (
+        "resync_snapshot",
+        include_str!("fixtures/resync_snapshot.json"),
+    ),
+    ("rejoin", include_str!("fixtures/rejoin.json")),
+    ("ack", include_str!("fixtures/ack.json")),
+    ("server_click", include_str!("fixtures/server_click.json")),
+    (
+        "server_take_turn",
+        include_str!("fixtures/server_take_turn.json"),
+    ),
+    ("server_state", include_str!("fixtures/server_state.json")),
+    ("join_room", include_str!("fixtures/join_room.json")),
+    ("room_players", include_str!("fixtures/room_players.json")),
+    ("start_game", include_str!("fixtures/start_game.json")),
+    (
+        "rematch_request",
+        include_str!("fixtures/rematch_request.json"),
+    ),
+    ("rematch", include_str!("fixtures/rematch.json")),
+    (
+        "server_rematch_request",
+        include_str!("fixtures/server_rematch_request.json"),
+    ),
+];
+
+///parse the fixture json
+fn parse_fixture(name: &str) -> WsMessage {
+    let (_, json) = FIXTURES
+        .iter()
+        .find(|(fixture_name, _)| *fixture_name == name)
+        .expect("unknown fixture");
+    serde_json::from_str(json).expect("fixture is not a WsMessage")
+}
+
+#[test]
+fn every_fixture_round_trips_to_the_same_json() {
+    for (name, json) in FIXTURES.iter() {
+        let ws_message: WsMessage = serde_json::from_str(json)
+            .unwrap_or_else(|err| panic!("{} is not a WsMessage: {}", name, err));
+        let serialized = serde_json::to_string(&ws_message).expect("error serialize");
+        let expected: Value = serde_json::from_str(json).expect("fixture is not json");
+        let actual: Value = serde_json::from_str(&serialized).expect("serialized is not json");
+        assert_eq!(expected, actual, "{} changed after the round trip", name);
+        let parsed_again: WsMessage = serde_json::from_str(&serialized).expect("error parse again");
+        assert_eq!(
+            ws_message, parsed_again,
+            "{} changed after the round trip",
+            name
+        );
+    }
+}
+
+#[test]
+fn hello_fields() {
+    match parse_fixture("hello") {
+        WsMessage::Hello {
+            ws_client_instance,
+            protocol_version,
+            features,
+            is_reply,
+        } => {
+            assert_eq!(ws_client_instance, 4321);
+            assert_eq!(protocol_version, 9);
+            assert!(features.iter().any(|feature| feature == "rooms"));
+            assert!(!is_reply);
+        }
+        _ => panic!("hello.json is not Hello"),
+    }
+}
+
+#[test]
+fn player_click_with_and_without_reveal() {
+    match parse_fixture("player_click") {
+        WsMessage::PlayerClick {
+            card_index,
+            card_reveal,
+            state_hash,
+            ..
+        } => {
+            assert_eq!(
+                card_reveal,
+                Some(CardReveal {
+                    card_index,
+                    card_number_and_img_src: 17,
+                    salt: "00000000deadbeef0000000012345678".to_string(),
+                })
+            );
+            assert!(state_hash.is_some());
+        }
+        _ => panic!("player_click.json is not PlayerClick"),
+    }
+    match parse_fixture("player_click_hidden") {
+        WsMessage::PlayerClick {
+            card_reveal,
+            state_hash,
+            ..
+        } => {
+            assert_eq!(card_reveal, None);
+            assert_eq!(state_hash, None);
+        }
+        _ => panic!("player_click_hidden.json is not PlayerClick"),
+    }
+}
+
+#[test]
+fn resync_snapshot_fields() {
+    match parse_fixture("resync_snapshot") {
+        WsMessage::ResyncSnapshot { game_snapshot, .. } => {
+            assert_eq!(game_snapshot.vec_card_statuses.len(), 7);
+            assert_eq!(
+                game_snapshot.vec_card_statuses[3],
+                CardStatusCardFace::UpTemporary
+            );
+            assert_eq!(game_snapshot.vec_card_reveals.len(), 3);
+            assert_eq!(game_snapshot.vec_points, vec![1, 0]);
+        }
+        _ => panic!("resync_snapshot.json is not ResyncSnapshot"),
+    }
+}
+
+#[test]
+fn server_state_has_a_player_for_every_score() {
+    match parse_fixture("server_state") {
+        WsMessage::ServerState {
+            game_snapshot,
+            vec_room_players,
+            ..
+        } => {
+            assert_eq!(vec_room_players.len(), game_snapshot.vec_points.len());
+            assert!(vec_room_players.len() <= MAX_PLAYERS);
+            assert_eq!(
+                vec_room_players[1],
+                RoomPlayer {
+                    ws_client_instance: 1234,
+                    player_name: "Ben".to_string(),
+                }
+            );
+        }
+        _ => panic!("server_state.json is not ServerState"),
+    }
+    match parse_fixture("room_players") {
+        WsMessage::RoomPlayers {
+            vec_room_players, ..
+        } => assert_eq!(vec_room_players[0].ws_client_instance, 4321),
+        _ => panic!("room_players.json is not RoomPlayers"),
+    }
+}
+
+#[test]
+fn only_the_game_stream_has_sequence_numbers() {
+    assert_eq!(parse_fixture("player_click").sequence(), Some((1234, 3)));
+    assert_eq!(parse_fixture("card_reveal").sequence(), Some((1234, 5)));
+    assert_eq!(parse_fixture("player_change").sequence(), Some((4321, 6)));
+    assert_eq!(parse_fixture("resync_request").sequence(), Some((4321, 7)));
+    assert_eq!(parse_fixture("resync_snapshot").sequence(), Some((1234, 8)));
+    assert_eq!(parse_fixture("rematch_request").sequence(), Some((4321, 9)));
+    assert_eq!(parse_fixture("rematch").sequence(), Some((1234, 10)));
+    assert_eq!(parse_fixture("hello").sequence(), None);
+    assert_eq!(parse_fixture("ack").sequence(), None);
+    assert_eq!(parse_fixture("rejoin").sequence(), None);
+    assert_eq!(parse_fixture("server_click").sequence(), None);
+    assert_eq!(parse_fixture("server_state").sequence(), None);
+    assert_eq!(parse_fixture("join_room").sequence(), None);
+    assert_eq!(parse_fixture("start_game").sequence(), None);
+    assert_eq!(parse_fixture("server_rematch_request").sequence(), None);
+}
+
+#[test]
+fn unknown_message_is_an_error() {
+    let result: Result<WsMessage, _> =
+        serde_json::from_str(r#"{"SurrenderGame":{"ws_client_instance":1}}"#);
+    assert!(result.is_err());
+    let result: Result<WsMessage, _> = serde_json::from_str(r#"{"Ack":{"ws_client_instance":1}}"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn card_round_trip() {
+    let card = Card {
+        status: CardStatusCardFace::UpPermanently,
+        card_number_and_img_src: 12,
+        card_index_and_id: 7,
+    };
+    let json = serde_json::to_string(&card).expect("error serialize");
+    assert_eq!(
+        json,
+        r#"{"status":"UpPermanently","card_number_and_img_src":12,"card_index_and_id":7}"#
+    );
+    let parsed: Card = serde_json::from_str(&json).expect("error parse");
+    assert_eq!(parsed, card);
+}
+
+#[test]
+fn grid_sizes_make_pairs() {
+    assert_eq!(GridSize::default(), GridSize { rows: 4, cols: 4 });
+    for grid_size in GRID_SIZES.iter() {
+        assert_eq!(grid_size.card_count() % 2, 0);
+        assert!(grid_size.is_possible(26));
+    }
+    assert!(!GridSize { rows: 3, cols: 3 }.is_possible(26));
+    assert!(!GridSize { rows: 6, cols: 6 }.is_possible(17));
+}
+
+#[test]
+fn a_huge_grid_size_from_the_wire_is_not_possible() {
+    for grid_size in [
+        GridSize {
+            rows: usize::MAX,
+            cols: usize::MAX,
+        },
+        GridSize {
+            rows: usize::MAX,
+            cols: 2,
+        },
+        //the product wraps to 0 without the check
+        GridSize {
+            rows: 1 << (usize::BITS - 1),
+            cols: 2,
+        },
+    ]
+    .iter()
+    {
+        assert_eq!(grid_size.card_count(), usize::MAX);
+        assert!(!grid_size.is_possible(usize::MAX));
+    }
+    //a grid size that is not one of the grid sizes to choose from
+    assert!(!GridSize { rows: 2, cols: 2 }.is_possible(26));
+}
@@ -0,0 +1,273 @@
+//! mem2_packcheck - checks the content packs before the deploy.
+//! A missing file in a pack shows up in the game only as a broken image
+//! or a sound that does not play. This check finds it before the players do.
+//! It reads the manifest `content/packs.json` and the cards file of every pack and checks:
+//! the card back, the images and sounds of the cards, the extra files that no card uses,
+//! the count of pairs against the manifest, the labels, the pair ids
+//! and that all the png images have the same dimensions as the card back.
+
+//region: use statements
+use mem2_common::{ContentPack, PackCards, PacksManifest, PACKS_MANIFEST_PATH};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+//endregion
+
+//region: enum, structs, const,...
+///the first 8 bytes of every png file
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+///one problem in a content pack
+#[derive(Clone, PartialEq, Debug)]
+pub enum PackProblem {
+    ///the cards file cannot be read or parsed
+    CardsFile {
+        ///the path of the cards file
+        path: PathBuf,
+        ///what went wrong
+        explanation: String,
+    },
+    ///a card back, image or sound in the cards file does not exist
+    MissingFile {
+        ///the path of the file
+        path: PathBuf,
+    },
+    ///a file in the folder of the images or sounds that no card uses
+    ExtraFile {
+        ///the path of the file
+        path: PathBuf,
+    },
+    ///the manifest promises another count of pairs than the cards file has
+    CardCountMismatch {
+        ///the card count in the manifest
+        manifest: usize,
+        ///the count of pairs in the cards file
+        cards_file: usize,
+    },
+    ///a card without the label for the header
+    EmptyLabel {
+        ///the card number, 1 is the first card
+        card_number: usize,
+    },
+    ///a pair id must have exactly 2 cards
+    UnpairedPairId {
+        ///the pair id
+        pair_id: String,
+        ///count of cards with this pair id
+        count: usize,
+    },
+    ///the image has other dimensions than the card back
+    ImageSizeMismatch {
+        ///the path of the image
+        path: PathBuf,
+        ///width and height of the image
+        size: (u32, u32),
+        ///width and height of the card back
+        card_back_size: (u32, u32),
+    },
+}
+//endregion
+
+impl fmt::Display for PackProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackProblem::CardsFile { path, explanation } => {
+                write!(f, "{}: {}", path.display(), explanation)
+            }
+            PackProblem::MissingFile { path } => write!(f, "{}: missing file", path.display()),
+            PackProblem::ExtraFile { path } => {
+                write!(f, "{}: extra file, no card uses it", path.display())
+            }
+            PackProblem::CardCountMismatch {
+                manifest,
+                cards_file,
+            } => write!(
+                f,
+                "the manifest has the card count {}, the cards file has {} pairs",
+                manifest, cards_file
+            ),
+            PackProblem::EmptyLabel { card_number } => {
+                write!(f, "the card {} has no label", card_number)
+            }
+            PackProblem::UnpairedPairId { pair_id, count } => write!(
+                f,
+                "the pair id {} has {} cards instead of 2",
+                pair_id, count
+            ),
+            PackProblem::ImageSizeMismatch {
+                path,
+                size,
+                card_back_size,
+            } => write!(
+                f,
+                "{}: {}x{} instead of {}x{} like the card back",
+                path.display(),
+                size.0,
+                size.1,
+                card_back_size.0,
+                card_back_size.1
+            ),
+        }
+    }
+}
+
+///read the manifest of the content packs from the web root
+pub fn read_packs_manifest(web_root: &Path) -> Result<PacksManifest, String> {
+    let path = web_root.join(PACKS_MANIFEST_PATH);
+    let json =
+        std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    serde_json::from_str(&json).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+///all the problems of one content pack. Empty if the pack is ready for the deploy.
+pub fn check_pack(web_root: &Path, content_pack: &ContentPack) -> Vec<PackProblem> {
+    let folder = web_root.join(&content_pack.folder);
+    let mut vec_problems = Vec::new();
+
+    let cards_file_path = folder.join(&content_pack.cards_file);
+    let pack_cards = match read_pack_cards(&cards_file_path) {
+        Ok(pack_cards) => pack_cards,
+        Err(explanation) => {
+            vec_problems.push(PackProblem::CardsFile {
+                path: cards_file_path,
+                explanation,
+            });
+            return vec_problems;
+        }
+    };
+
+    if pack_cards.pair_count() != content_pack.card_count {
+        vec_problems.push(PackProblem::CardCountMismatch {
+            manifest: content_pack.card_count,
+            cards_file: pack_cards.pair_count(),
+        });
+    }
+    for (index, card_metadata) in pack_cards.cards.iter().enumerate() {
+        if card_metadata.label.trim().is_empty() {
+            vec_problems.push(PackProblem::EmptyLabel {
+                card_number: index + 1,
+            });
+        }
+    }
+    vec_problems.extend(unpaired_pair_ids(&pack_cards));
+
+    //region: the files the pack uses
+    let card_back_path = folder.join(&content_pack.card_back);
+    let mut vec_images = vec![card_back_path.clone()];
+    vec_images.extend(
+        pack_cards
+            .cards
+            .iter()
+            .map(|card_metadata| folder.join(&card_metadata.image)),
+    );
+    let vec_sounds: Vec<PathBuf> = pack_cards
+        .cards
+        .iter()
+        .filter_map(|card_metadata| card_metadata.sound.as_ref())
+        .map(|sound| folder.join(sound))
+        .collect();
+    for path in vec_images.iter().chain(vec_sounds.iter()) {
+        if !path.is_file() {
+            vec_problems.push(PackProblem::MissingFile { path: path.clone() });
+        }
+    }
+    //endregion
+
+    //region: the files in the same folders that no card uses
+    let used_files: BTreeSet<PathBuf> = vec_images
+        .iter()
+        .chain(vec_sounds.iter())
+        .map(|path| normalized(path))
+        .collect();
+    let used_folders: BTreeSet<PathBuf> = used_files
+        .iter()
+        .filter_map(|path| path.parent().map(Path::to_path_buf))
+        .collect();
+    for used_folder in &used_folders {
+        let mut vec_entries: Vec<PathBuf> = match std::fs::read_dir(used_folder) {
+            Ok(read_dir) => read_dir
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect(),
+            Err(_) => continue,
+        };
+        vec_entries.sort();
+        for path in vec_entries {
+            if !used_files.contains(&normalized(&path)) {
+                vec_problems.push(PackProblem::ExtraFile { path });
+            }
+        }
+    }
+    //endregion
+
+    //region: the png images have the dimensions of the card back
+    if let Some(card_back_size) = png_size(&card_back_path) {
+        for path in vec_images.iter().skip(1) {
+            if let Some(size) = png_size(path) {
+                if size != card_back_size {
+                    vec_problems.push(PackProblem::ImageSizeMismatch {
+                        path: path.clone(),
+                        size,
+                        card_back_size,
+                    });
+                }
+            }
+        }
+    }
+    //endregion
+
+    vec_problems
+}
+
+///read and parse the cards file of a pack
+fn read_pack_cards(path: &Path) -> Result<PackCards, String> {
+    let json = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    serde_json::from_str(&json).map_err(|err| err.to_string())
+}
+
+///the pair ids that don't have exactly 2 cards, in the order of their first card
+fn unpaired_pair_ids(pack_cards: &PackCards) -> Vec<PackProblem> {
+    let mut vec_pair_ids: Vec<&String> = Vec::new();
+    for card_metadata in &pack_cards.cards {
+        if let Some(pair_id) = &card_metadata.pair_id {
+            if !vec_pair_ids.contains(&pair_id) {
+                vec_pair_ids.push(pair_id);
+            }
+        }
+    }
+    vec_pair_ids
+        .into_iter()
+        .filter_map(|pair_id| {
+            let count = pack_cards
+                .cards
+                .iter()
+                .filter(|card_metadata| card_metadata.pair_id.as_ref() == Some(pair_id))
+                .count();
+            if count == 2 {
+                None
+            } else {
+                Some(PackProblem::UnpairedPairId {
+                    pair_id: pair_id.clone(),
+                    count,
+                })
+            }
+        })
+        .collect()
+}
+
+///the same file has the same path, also if the cards file uses ./ or ../
+fn normalized(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+///width and height from the IHDR chunk of a png file. None for other files.
+pub fn png_size(path: &Path) -> Option<(u32, u32)> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.len() < 24 || bytes[..8] != PNG_SIGNATURE || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    Some((width, height))
+}
@@ -0,0 +1,78 @@
+//! mem2_packcheck - the command line for the check of the content packs.
+//! `cargo run -p mem2_packcheck -- --dir .` checks all the packs in the manifest.
+//! The names of pack folders after the arguments check only these packs.
+//! The exit code is 1 if there is any problem.
+
+//region: use statements
+use clap::{App, Arg};
+use mem2_packcheck::{check_pack, read_packs_manifest};
+use std::path::PathBuf;
+//endregion
+
+///parse the arguments, check the packs and print the problems
+fn main() {
+    let matches = App::new("mem2_packcheck")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("checks the content packs of the mem2 game before the deploy")
+        .arg(
+            Arg::with_name("dir")
+                .long("dir")
+                .value_name("DIR")
+                .default_value(".")
+                .help("the web root with content/packs.json and the content folders"),
+        )
+        .arg(
+            Arg::with_name("folder")
+                .value_name("FOLDER")
+                .multiple(true)
+                .help("the folders of the packs to check. All the packs if none."),
+        )
+        .get_matches();
+    let web_root = PathBuf::from(matches.value_of("dir").unwrap_or("."));
+
+    let packs_manifest = match read_packs_manifest(&web_root) {
+        Ok(packs_manifest) => packs_manifest,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let vec_folders: Vec<String> = match matches.values_of("folder") {
+        Some(values) => values.map(String::from).collect(),
+        None => packs_manifest
+            .packs
+            .iter()
+            .map(|content_pack| content_pack.folder.clone())
+            .collect(),
+    };
+
+    let mut problem_count = 0;
+    for folder in &vec_folders {
+        match packs_manifest.find(folder) {
+            Some(content_pack) => {
+                let vec_problems = check_pack(&web_root, content_pack);
+                if vec_problems.is_empty() {
+                    println!("{} ({}): ok", content_pack.folder, content_pack.name);
+                } else {
+                    println!(
+                        "{} ({}): {} problems",
+                        content_pack.folder,
+                        content_pack.name,
+                        vec_problems.len()
+                    );
+                    for pack_problem in &vec_problems {
+                        println!("    {}", pack_problem);
+                    }
+                }
+                problem_count += vec_problems.len();
+            }
+            None => {
+                println!("{}: the folder is not in the manifest", folder);
+                problem_count += 1;
+            }
+        }
+    }
+    if problem_count > 0 {
+        std::process::exit(1);
+    }
+}
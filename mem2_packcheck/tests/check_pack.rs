@@ -0,0 +1,157 @@
+//! A pack folder is built in the temp dir with exactly the problems of the test.
+
+use mem2_common::ContentPack;
+use mem2_packcheck::{check_pack, png_size, read_packs_manifest, PackProblem};
+use std::path::{Path, PathBuf};
+
+///an empty web root in the temp dir, unique for the test
+fn web_root(test_name: &str) -> PathBuf {
+    let web_root = std::env::temp_dir().join(format!(
+        "mem2_packcheck_{}_{}",
+        test_name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&web_root);
+    std::fs::create_dir_all(web_root.join("pack/img")).expect("create_dir_all");
+    std::fs::create_dir_all(web_root.join("pack/sound")).expect("create_dir_all");
+    web_root
+}
+
+///the png signature and the IHDR chunk are enough for the dimensions
+fn write_png(path: &Path, width: u32, height: u32) {
+    let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 13];
+    bytes.extend_from_slice(b"IHDR");
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    std::fs::write(path, bytes).expect("write png");
+}
+
+///the pack in the folder "pack" with 2 cards
+fn content_pack(card_count: usize) -> ContentPack {
+    ContentPack {
+        name: String::from("Test"),
+        folder: String::from("pack"),
+        card_count,
+        card_back: String::from("img/back.png"),
+        cards_file: String::from("cards.json"),
+    }
+}
+
+///a pack without problems
+fn write_good_pack(web_root: &Path) {
+    write_png(&web_root.join("pack/img/back.png"), 116, 116);
+    write_png(&web_root.join("pack/img/one.png"), 116, 116);
+    write_png(&web_root.join("pack/img/two.png"), 116, 116);
+    std::fs::write(web_root.join("pack/sound/one.mp3"), b"mp3").expect("write mp3");
+    std::fs::write(
+        web_root.join("pack/cards.json"),
+        r#"{"cards":[
+            {"image":"img/one.png","sound":"sound/one.mp3","label":"one"},
+            {"image":"img/two.png","label":"two"}
+        ]}"#,
+    )
+    .expect("write cards.json");
+}
+
+#[test]
+fn a_good_pack_has_no_problems() {
+    let web_root = web_root("good");
+    write_good_pack(&web_root);
+    assert_eq!(check_pack(&web_root, &content_pack(2)), vec![]);
+    assert_eq!(
+        png_size(&web_root.join("pack/img/back.png")),
+        Some((116, 116))
+    );
+    assert_eq!(png_size(&web_root.join("pack/sound/one.mp3")), None);
+}
+
+#[test]
+fn missing_and_extra_files() {
+    let web_root = web_root("files");
+    write_good_pack(&web_root);
+    std::fs::remove_file(web_root.join("pack/img/back.png")).expect("remove");
+    std::fs::remove_file(web_root.join("pack/sound/one.mp3")).expect("remove");
+    write_png(&web_root.join("pack/img/three.png"), 116, 116);
+    std::fs::write(web_root.join("pack/sound/two.mp3"), b"mp3").expect("write mp3");
+    let vec_problems = check_pack(&web_root, &content_pack(2));
+    assert!(vec_problems.contains(&PackProblem::MissingFile {
+        path: web_root.join("pack/img/back.png")
+    }));
+    assert!(vec_problems.contains(&PackProblem::MissingFile {
+        path: web_root.join("pack/sound/one.mp3")
+    }));
+    assert!(vec_problems.contains(&PackProblem::ExtraFile {
+        path: web_root.join("pack/img/three.png")
+    }));
+    assert!(vec_problems.contains(&PackProblem::ExtraFile {
+        path: web_root.join("pack/sound/two.mp3")
+    }));
+    assert_eq!(vec_problems.len(), 4);
+}
+
+#[test]
+fn count_labels_pairs_and_sizes() {
+    let web_root = web_root("cards");
+    write_good_pack(&web_root);
+    write_png(&web_root.join("pack/img/two.png"), 115, 116);
+    std::fs::write(
+        web_root.join("pack/cards.json"),
+        r#"{"cards":[
+            {"image":"img/one.png","sound":"sound/one.mp3","label":"one","pair_id":"x"},
+            {"image":"img/two.png","label":" ","pair_id":"y"}
+        ]}"#,
+    )
+    .expect("write cards.json");
+    let vec_problems = check_pack(&web_root, &content_pack(2));
+    assert_eq!(
+        vec_problems,
+        vec![
+            PackProblem::CardCountMismatch {
+                manifest: 2,
+                cards_file: 0
+            },
+            PackProblem::EmptyLabel { card_number: 2 },
+            PackProblem::UnpairedPairId {
+                pair_id: String::from("x"),
+                count: 1
+            },
+            PackProblem::UnpairedPairId {
+                pair_id: String::from("y"),
+                count: 1
+            },
+            PackProblem::ImageSizeMismatch {
+                path: web_root.join("pack/img/two.png"),
+                size: (115, 116),
+                card_back_size: (116, 116)
+            },
+        ]
+    );
+}
+
+#[test]
+fn the_cards_file_cannot_be_parsed() {
+    let web_root = web_root("parse");
+    write_good_pack(&web_root);
+    std::fs::write(web_root.join("pack/cards.json"), "{").expect("write cards.json");
+    match check_pack(&web_root, &content_pack(2)).as_slice() {
+        [PackProblem::CardsFile { path, .. }] => {
+            assert_eq!(path, &web_root.join("pack/cards.json"))
+        }
+        vec_problems => panic!("unexpected {:?}", vec_problems),
+    }
+}
+
+#[test]
+fn the_packs_of_this_repository_have_all_the_files() {
+    let web_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    let packs_manifest = read_packs_manifest(&web_root).expect("content/packs.json");
+    for content_pack in &packs_manifest.packs {
+        for pack_problem in check_pack(&web_root, content_pack) {
+            //2 images of the alphabet are one pixel narrower than the card back
+            if let PackProblem::ImageSizeMismatch { .. } = pack_problem {
+                continue;
+            }
+            panic!("{}: {}", content_pack.folder, pack_problem);
+        }
+    }
+}
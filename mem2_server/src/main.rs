@@ -0,0 +1,931 @@
+//! mem2_server - html and WebSocket server for the mem2 game.
+//! It serves the static files of the game: index.html, favicon.ico, pkg/, css/ and the content folders.
+//! On the route /mem2ws/ it relays the WebSocket messages between the players.
+//! The relay does not need to understand the game. From the json it reads only
+//! the name of the message, the ws_client_instance and the room_code.
+//! The game messages go only to the players in the same room, all other messages go to everybody.
+//! A game message from a player without a room is dropped. The last player that leaves a room removes it.
+//! The sender receives the echo of its own message too.
+//! The first message binds its ws_client_instance to the connection.
+//! The messages with the ws_client_instance of another player are dropped.
+//! A reconnect with the same ws_client_instance replaces the old connection.
+//! In the server authoritative mode the server is the referee of the game, see `servergame.rs`.
+
+mod servergame;
+
+//region: use statements
+use clap::{App, Arg};
+use futures_util::{SinkExt, StreamExt};
+use log::{error, info, warn};
+use mem2_common::gameengine::RuleViolation;
+use mem2_common::{
+    CardPair, GridSize, PackCards, PacksManifest, RoomPlayer, WsMessage, CARD_IMAGES_COUNT,
+    MAX_PLAYERS, PACKS_MANIFEST_PATH,
+};
+use serde_json::Value;
+use servergame::ServerGame;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use warp::filters::BoxedFilter;
+use warp::ws::{Message, WebSocket};
+use warp::Filter;
+//endregion
+
+//region: enum, structs, const,...
+///the messages of the game stream. They go only to the players in the same room.
+const ROOM_MESSAGES: [&str; 10] = [
+    "PlayerClick",
+    "CardReveal",
+    "PlayerChange",
+    "ResyncRequest",
+    "ResyncSnapshot",
+    "Ack",
+    "Rejoin",
+    "RematchRequest",
+    "Rematch",
+    "ServerRematchRequest",
+];
+
+///unique id of the connection on the server. It is not the ws_client_instance.
+static NEXT_CONNECTION_ID: AtomicUsize = AtomicUsize::new(1);
+
+///one WebSocket connection
+struct Connection {
+    ///the client sends its ws_client_instance with every message.
+    ///0 until the first message binds it to the connection.
+    ws_client_instance: usize,
+    ///the messages for this connection
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+///the state of the relay shared between all connections
+#[derive(Default)]
+struct Relay {
+    ///all open connections by connection id
+    connections: HashMap<usize, Connection>,
+    ///the room of every ws_client_instance.
+    ///It stays after a disconnect while other players are in the room,
+    ///so the player can reconnect and rejoin the room.
+    ///The players of the rooms of the server referee are added only when the referee accepts them.
+    rooms: HashMap<usize, String>,
+    ///server authoritative rooms waiting for the host to start the game by room code
+    waiting_server_games: HashMap<String, WaitingServerGame>,
+    ///the games where the server is the referee by room code
+    server_games: HashMap<String, ServerGame>,
+    ///the content packs. The server referee builds the deck with the card count of the pack.
+    packs_manifest: PacksManifest,
+    ///the pairs of different faces by the folder of the pack. Only the packs with pair ids.
+    pack_pairs: HashMap<String, Vec<CardPair>>,
+}
+
+///a server authoritative room before the host starts the game
+struct WaitingServerGame {
+    ///the host that created the room is the first, then the players in the order they joined
+    vec_room_players: Vec<RoomPlayer>,
+    ///grid size chosen by the host
+    grid_size: GridSize,
+    ///count of different pairs in the content pack of the room
+    card_count: usize,
+    ///the pairs of different faces in the content pack of the room
+    vec_pairs: Vec<CardPair>,
+}
+
+///the relay is used by all connections
+type SharedRelay = Arc<Mutex<Relay>>;
+
+///the only fields of the message the relay needs
+struct MessageHeader {
+    ///the name of the WsMessage variant
+    message_name: String,
+    ///the sender
+    ws_client_instance: Option<usize>,
+    ///only the messages that open, join or start a room have the room code
+    room_code: Option<String>,
+}
+//endregion
+
+#[tokio::main]
+///parse the arguments, prepare the routes and run the server forever
+async fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let matches = App::new("mem2_server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("html and WebSocket server for the mem2 game")
+        .arg(
+            Arg::with_name("port")
+                .long("port")
+                .value_name("PORT")
+                .default_value("8086")
+                .help("the http and WebSocket port"),
+        )
+        .arg(
+            Arg::with_name("dir")
+                .long("dir")
+                .value_name("DIR")
+                .default_value(".")
+                .help("the folder with index.html, pkg/, css/ and the content folders"),
+        )
+        .get_matches();
+    let port: u16 = matches
+        .value_of("port")
+        .unwrap_or("8086")
+        .parse()
+        .expect("error: the port is not a number");
+    let dir = PathBuf::from(matches.value_of("dir").unwrap_or("."));
+
+    if !dir.join("index.html").exists() {
+        error!("{} has no index.html", dir.display());
+        std::process::exit(1);
+    }
+    if !dir.join("pkg").exists() {
+        warn!("there is no pkg/ folder. Build it with `wasm-pack build --target web`.");
+    }
+
+    let packs_manifest = load_packs_manifest(&dir);
+    let relay = SharedRelay::new(Mutex::new(Relay {
+        pack_pairs: load_pack_pairs(&dir, &packs_manifest),
+        packs_manifest,
+        ..Relay::default()
+    }));
+    let relay = warp::any().map(move || relay.clone());
+    //the WebSocket route is the same as in the client: <origin>/mem2ws/
+    let mem2ws = warp::path("mem2ws")
+        .and(warp::ws())
+        .and(relay)
+        .map(|ws: warp::ws::Ws, relay| {
+            ws.on_upgrade(move |socket| connection_opened(socket, relay))
+        });
+    let routes = mem2ws.or(static_files(&dir));
+
+    match local_ip_address() {
+        Some(ip) => info!("Open the browser at http://{}:{}/", ip, port),
+        None => info!("Open the browser at http://127.0.0.1:{}/", port),
+    }
+    warp::serve(routes)
+        .run(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port))
+        .await;
+}
+
+///the manifest of the content packs. Without it the server referee uses the default card count.
+fn load_packs_manifest(dir: &Path) -> PacksManifest {
+    let path = dir.join(PACKS_MANIFEST_PATH);
+    let packs_manifest = std::fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()));
+    match packs_manifest {
+        Ok(packs_manifest) => packs_manifest,
+        Err(err) => {
+            warn!("{}: {}", path.display(), err);
+            PacksManifest::default()
+        }
+    }
+}
+
+///the pairs of different faces of every pack. A pack without a cards file is a classic pack.
+fn load_pack_pairs(dir: &Path, packs_manifest: &PacksManifest) -> HashMap<String, Vec<CardPair>> {
+    let mut pack_pairs = HashMap::new();
+    for content_pack in &packs_manifest.packs {
+        let path = dir
+            .join(&content_pack.folder)
+            .join(&content_pack.cards_file);
+        let pack_cards = std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|json| {
+                serde_json::from_str::<PackCards>(&json).map_err(|err| err.to_string())
+            });
+        match pack_cards {
+            Ok(pack_cards) => {
+                let vec_pairs = pack_cards.pairs();
+                if !vec_pairs.is_empty() {
+                    pack_pairs.insert(content_pack.folder.clone(), vec_pairs);
+                }
+            }
+            Err(err) => warn!("{}: {}", path.display(), err),
+        }
+    }
+    pack_pairs
+}
+
+///only the files the game needs, not the source code
+fn static_files(dir: &Path) -> BoxedFilter<(warp::fs::File,)> {
+    let index = warp::path::end().and(warp::fs::file(dir.join("index.html")));
+    let favicon = warp::path("favicon.ico")
+        .and(warp::path::end())
+        .and(warp::fs::file(dir.join("favicon.ico")));
+    let mut routes = index.or(favicon).unify().boxed();
+    for folder_name in static_folder_names(dir) {
+        let folder = warp::path(folder_name.clone()).and(warp::fs::dir(dir.join(&folder_name)));
+        routes = routes.or(folder).unify().boxed();
+    }
+    routes
+}
+
+///pkg/ with the wasm code, css/ and all the folders that start with content
+fn static_folder_names(dir: &Path) -> Vec<String> {
+    let mut vec_folder_names = vec!["pkg".to_string(), "css".to_string()];
+    if let Ok(read_dir) = dir.read_dir() {
+        for entry in read_dir.filter_map(Result::ok) {
+            let folder_name = entry.file_name().to_string_lossy().to_string();
+            if folder_name.starts_with("content") && entry.path().is_dir() {
+                vec_folder_names.push(folder_name);
+            }
+        }
+    }
+    vec_folder_names.sort();
+    vec_folder_names
+}
+
+///the address of this machine in the local network. The smartphones on the same WiFi use it.
+fn local_ip_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    //no packet is sent. Connect only chooses the network interface.
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+///every connection has a task that sends the messages from the channel to the WebSocket.
+///The received messages are relayed until the connection is closed.
+async fn connection_opened(ws: WebSocket, relay: SharedRelay) {
+    let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+    info!("connection {} opened", connection_id);
+
+    let (mut ws_tx, mut ws_rx) = ws.split();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut rx = UnboundedReceiverStream::new(rx);
+    tokio::task::spawn(async move {
+        while let Some(message) = rx.next().await {
+            if let Err(err) = ws_tx.send(message).await {
+                info!("connection {} send error: {}", connection_id, err);
+                break;
+            }
+        }
+    });
+    relay.lock().expect("error relay lock").connections.insert(
+        connection_id,
+        Connection {
+            ws_client_instance: 0,
+            tx,
+        },
+    );
+
+    while let Some(result) = ws_rx.next().await {
+        let message = match result {
+            Ok(message) => message,
+            Err(err) => {
+                info!("connection {} receive error: {}", connection_id, err);
+                break;
+            }
+        };
+        if let Ok(text) = message.to_str() {
+            relay_message(connection_id, text, &relay);
+        }
+    }
+
+    connection_closed(connection_id, &relay);
+}
+
+///the connection is removed. The last player that leaves a room removes it.
+///A connection replaced by a reconnect was removed before.
+fn connection_closed(connection_id: usize, relay: &SharedRelay) {
+    let mut relay = relay.lock().expect("error relay lock");
+    if let Some(connection) = relay.connections.remove(&connection_id) {
+        if let Some(room_code) = relay.rooms.get(&connection.ws_client_instance).cloned() {
+            remove_room_if_empty(&mut relay, &room_code);
+        }
+    }
+    info!("connection {} closed", connection_id);
+}
+
+///the game messages go only to the room of the sender, all other messages to everybody.
+///The sender receives the echo too. The clients use it to know the order of the messages.
+fn relay_message(connection_id: usize, text: &str, relay: &SharedRelay) {
+    let message_header = parse_message_header(text);
+    let is_room_message = ROOM_MESSAGES.contains(&message_header.message_name.as_str());
+    let ws_message: Option<WsMessage> = serde_json::from_str(text).ok();
+    let mut relay = relay.lock().expect("error relay lock");
+    let sender = match message_header.ws_client_instance {
+        Some(ws_client_instance) => {
+            if !bind_ws_client_instance(&mut relay, connection_id, ws_client_instance) {
+                info!(
+                    "connection {} dropped a message of the player {}",
+                    connection_id, ws_client_instance
+                );
+                return;
+            }
+            if let Some(room_code) = message_header.room_code {
+                //the referee puts the players in its rooms only when it accepts them
+                let is_referee_room = is_referee_message(&ws_message)
+                    || relay.waiting_server_games.contains_key(&room_code)
+                    || relay.server_games.contains_key(&room_code);
+                if !is_referee_room {
+                    enter_room(&mut relay, ws_client_instance, room_code);
+                }
+            }
+            Some(ws_client_instance)
+        }
+        //only the connection test of a new connection has no sender.
+        //The messages of the server come only from the server.
+        None if message_header.message_name == "ConnectionTest" => None,
+        None => {
+            info!(
+                "connection {} dropped a message without the sender",
+                connection_id
+            );
+            return;
+        }
+    };
+    let sender_room_code = sender
+        .and_then(|ws_client_instance| relay.rooms.get(&ws_client_instance))
+        .cloned();
+    //a game message without a room would go to everybody
+    if is_room_message && sender_room_code.is_none() {
+        info!(
+            "connection {} dropped a game message without a room",
+            connection_id
+        );
+        return;
+    }
+    //the moves and the joins of the server authoritative mode are not relayed.
+    //The server answers with its state or with the players of the room.
+    match ws_message {
+        Some(WsMessage::ServerClick { .. })
+        | Some(WsMessage::ServerTakeTurn { .. })
+        | Some(WsMessage::JoinRoom { .. }) => {}
+        _ => {
+            let receiver_room_code = sender_room_code.filter(|_| is_room_message);
+            for connection in relay.connections.values() {
+                let is_receiver = match &receiver_room_code {
+                    Some(room_code) => {
+                        relay.rooms.get(&connection.ws_client_instance) == Some(room_code)
+                    }
+                    None => true,
+                };
+                if is_receiver {
+                    //the connection can be closing. It is removed when its receive loop ends.
+                    let _ = connection.tx.send(Message::text(text));
+                }
+            }
+        }
+    }
+    if let (Some(sender), Some(ws_message)) = (sender, ws_message) {
+        referee(&mut relay, sender, ws_message);
+    }
+}
+
+///the player is in the room now. The room the player was in before can be empty.
+fn enter_room(relay: &mut Relay, ws_client_instance: usize, room_code: String) {
+    if let Some(old_room_code) = relay.rooms.insert(ws_client_instance, room_code.clone()) {
+        if old_room_code != room_code {
+            remove_room_if_empty(relay, &old_room_code);
+        }
+    }
+}
+
+///the room and its game are removed when no open connection is in the room.
+///While some player is still in the room, the others can reconnect and rejoin it.
+fn remove_room_if_empty(relay: &mut Relay, room_code: &str) {
+    let is_empty = !relay.connections.values().any(|connection| {
+        relay
+            .rooms
+            .get(&connection.ws_client_instance)
+            .map(String::as_str)
+            == Some(room_code)
+    });
+    if is_empty {
+        relay
+            .rooms
+            .retain(|_, player_room_code| player_room_code != room_code);
+        relay.waiting_server_games.remove(room_code);
+        relay.server_games.remove(room_code);
+        info!("the room {} is removed", room_code);
+    }
+}
+
+///the messages that open or join a room of the server referee
+fn is_referee_message(ws_message: &Option<WsMessage>) -> bool {
+    matches!(
+        ws_message,
+        Some(WsMessage::WantToPlay {
+            is_server_authoritative: true,
+            ..
+        }) | Some(WsMessage::JoinRoom { .. })
+    )
+}
+
+///the first message binds its ws_client_instance to the connection.
+///A player that reconnects comes with the same ws_client_instance on a new connection,
+///while the old connection can still look open. The new connection replaces the old one.
+///Returns false for a message with the ws_client_instance of another player.
+fn bind_ws_client_instance(
+    relay: &mut Relay,
+    connection_id: usize,
+    ws_client_instance: usize,
+) -> bool {
+    match relay.connections.get(&connection_id) {
+        Some(connection) if connection.ws_client_instance == 0 && ws_client_instance != 0 => {}
+        Some(connection) => {
+            return connection.ws_client_instance != 0
+                && connection.ws_client_instance == ws_client_instance
+        }
+        None => return false,
+    }
+    let vec_stale_connection_ids: Vec<usize> = relay
+        .connections
+        .iter()
+        .filter(|(_, connection)| connection.ws_client_instance == ws_client_instance)
+        .map(|(stale_connection_id, _)| *stale_connection_id)
+        .collect();
+    for stale_connection_id in vec_stale_connection_ids {
+        //without the sender the send task ends. The messages still received on it are dropped.
+        relay.connections.remove(&stale_connection_id);
+        info!(
+            "connection {} replaces the connection {} of the player {}",
+            connection_id, stale_connection_id, ws_client_instance
+        );
+    }
+    if let Some(connection) = relay.connections.get_mut(&connection_id) {
+        connection.ws_client_instance = ws_client_instance;
+    }
+    true
+}
+
+///the server authoritative mode. After every move the server state goes to the room.
+///The sender is the ws_client_instance bound to the connection, not the one in the message.
+fn referee(relay: &mut Relay, sender: usize, ws_message: WsMessage) {
+    match ws_message {
+        WsMessage::WantToPlay {
+            room_code,
+            grid_size,
+            is_server_authoritative: true,
+            content_folder_name,
+            player_name,
+            ..
+        } => {
+            //the host announces the room again to every new client. The players that joined stay.
+            if let Some(waiting_server_game) = relay.waiting_server_games.get(&room_code) {
+                if waiting_server_game.vec_room_players[0].ws_client_instance == sender {
+                    enter_room(relay, sender, room_code.clone());
+                    send_room_players(relay, &room_code);
+                    return;
+                }
+            }
+            let card_count = relay
+                .packs_manifest
+                .find(&content_folder_name)
+                .map_or(CARD_IMAGES_COUNT, |content_pack| content_pack.card_count);
+            //the deck cannot be built if there are not enough images
+            if grid_size.is_possible(card_count) {
+                relay.waiting_server_games.insert(
+                    room_code.clone(),
+                    WaitingServerGame {
+                        vec_room_players: vec![RoomPlayer {
+                            ws_client_instance: sender,
+                            player_name,
+                        }],
+                        grid_size,
+                        card_count,
+                        vec_pairs: relay
+                            .pack_pairs
+                            .get(&content_folder_name)
+                            .cloned()
+                            .unwrap_or_default(),
+                    },
+                );
+                enter_room(relay, sender, room_code.clone());
+                send_room_players(relay, &room_code);
+            } else {
+                info!("the grid size is not possible in the room {}", room_code);
+            }
+        }
+        WsMessage::JoinRoom {
+            room_code,
+            player_name,
+            ..
+        } => {
+            let vec_room_players = match relay.waiting_server_games.get_mut(&room_code) {
+                Some(waiting_server_game) => &mut waiting_server_game.vec_room_players,
+                None => return,
+            };
+            if vec_room_players
+                .iter()
+                .any(|room_player| room_player.ws_client_instance == sender)
+            {
+                //the same player joined again after a reconnect
+            } else if vec_room_players.len() < MAX_PLAYERS {
+                vec_room_players.push(RoomPlayer {
+                    ws_client_instance: sender,
+                    player_name,
+                });
+            } else {
+                info!("the room {} is full", room_code);
+                //only the player that did not fit sees the full room. It does not enter the room.
+                let ws_message = WsMessage::RoomPlayers {
+                    room_code,
+                    vec_room_players: vec_room_players.clone(),
+                };
+                send_to_player(relay, sender, &ws_message);
+                return;
+            }
+            enter_room(relay, sender, room_code.clone());
+            send_room_players(relay, &room_code);
+        }
+        WsMessage::StartGame { room_code, .. } => {
+            //only the host starts and the game needs at least 2 players
+            let can_start = matches!(
+                relay.waiting_server_games.get(&room_code),
+                Some(waiting_server_game) if waiting_server_game.vec_room_players.len() >= 2
+                    && waiting_server_game.vec_room_players[0].ws_client_instance == sender
+            );
+            if !can_start {
+                info!("the room {} cannot start", room_code);
+                return;
+            }
+            if let Some(waiting_server_game) = relay.waiting_server_games.remove(&room_code) {
+                info!(
+                    "the server is the referee in the room {} with {} players",
+                    room_code,
+                    waiting_server_game.vec_room_players.len()
+                );
+                match ServerGame::new(
+                    room_code.clone(),
+                    waiting_server_game.grid_size,
+                    waiting_server_game.card_count,
+                    waiting_server_game.vec_pairs,
+                    waiting_server_game.vec_room_players,
+                ) {
+                    Ok(server_game) => {
+                        relay.server_games.insert(room_code.clone(), server_game);
+                        send_server_state(relay, &room_code);
+                    }
+                    Err(error) => warn!("the room {} cannot start: {}", room_code, error),
+                }
+            }
+        }
+        WsMessage::ServerClick { card_index, .. } => referee_move(relay, sender, |server_game| {
+            server_game.click(sender, card_index)
+        }),
+        WsMessage::ServerTakeTurn { .. } => {
+            referee_move(relay, sender, |server_game| server_game.take_turn(sender))
+        }
+        //the state goes to the room only when the fresh deck is dealt
+        WsMessage::ServerRematchRequest { .. } => {
+            let room_code = match relay.rooms.get(&sender) {
+                Some(room_code) => room_code.clone(),
+                None => return,
+            };
+            if let Some(server_game) = relay.server_games.get_mut(&room_code) {
+                if server_game.request_rematch(sender) {
+                    info!("rematch in the room {}", room_code);
+                    send_server_state(relay, &room_code);
+                }
+            }
+        }
+        //the player reconnected and needs the state
+        WsMessage::Rejoin { .. } => referee_move(relay, sender, |_| Ok(())),
+        _ => {}
+    }
+}
+
+///apply the move to the game in the room of the player and send the state.
+///A rejected move sends the state too, so the player sees the move did not happen.
+fn referee_move(
+    relay: &mut Relay,
+    ws_client_instance: usize,
+    apply_move: impl FnOnce(&mut ServerGame) -> Result<(), RuleViolation>,
+) {
+    let room_code = match relay.rooms.get(&ws_client_instance) {
+        Some(room_code) => room_code.clone(),
+        None => return,
+    };
+    if let Some(server_game) = relay.server_games.get_mut(&room_code) {
+        if let Err(rule_violation) = apply_move(server_game) {
+            info!(
+                "rejected move of player {}: {}",
+                ws_client_instance, rule_violation
+            );
+        }
+        send_server_state(relay, &room_code);
+    }
+}
+
+///the server state goes to all the players in the room
+fn send_server_state(relay: &Relay, room_code: &str) {
+    if let Some(server_game) = relay.server_games.get(room_code) {
+        match server_game.server_state() {
+            Some(ws_message) => send_to_room(relay, room_code, &ws_message),
+            None => error!("the server has no snapshot of the room {}", room_code),
+        }
+    }
+}
+
+///the players that joined the room go to everybody in the room
+fn send_room_players(relay: &Relay, room_code: &str) {
+    if let Some(waiting_server_game) = relay.waiting_server_games.get(room_code) {
+        let ws_message = WsMessage::RoomPlayers {
+            room_code: room_code.to_string(),
+            vec_room_players: waiting_server_game.vec_room_players.clone(),
+        };
+        send_to_room(relay, room_code, &ws_message);
+    }
+}
+
+///the message from the server goes to all the connections in the room
+fn send_to_room(relay: &Relay, room_code: &str, ws_message: &WsMessage) {
+    let text = serde_json::to_string(ws_message).expect("error serde_json WsMessage");
+    for connection in relay.connections.values() {
+        if relay
+            .rooms
+            .get(&connection.ws_client_instance)
+            .map(String::as_str)
+            == Some(room_code)
+        {
+            //the connection can be closing. It is removed when its receive loop ends.
+            let _ = connection.tx.send(Message::text(text.as_str()));
+        }
+    }
+}
+
+///the message from the server goes only to the connection of the player
+fn send_to_player(relay: &Relay, ws_client_instance: usize, ws_message: &WsMessage) {
+    let text = serde_json::to_string(ws_message).expect("error serde_json WsMessage");
+    for connection in relay.connections.values() {
+        if connection.ws_client_instance == ws_client_instance {
+            //the connection can be closing. It is removed when its receive loop ends.
+            let _ = connection.tx.send(Message::text(text.as_str()));
+        }
+    }
+}
+
+///the json of a WsMessage looks like
+///{"WantToPlay":{"ws_client_instance":123,"room_code":"K7QX","grid_size":{"rows":4,"cols":4}}}
+fn parse_message_header(text: &str) -> MessageHeader {
+    let value: Value = serde_json::from_str(text).unwrap_or(Value::Null);
+    match value.as_object().and_then(|object| object.iter().next()) {
+        Some((message_name, fields)) => MessageHeader {
+            message_name: message_name.to_string(),
+            ws_client_instance: fields
+                .get("ws_client_instance")
+                .and_then(Value::as_u64)
+                .and_then(|ws_client_instance| usize::try_from(ws_client_instance).ok()),
+            room_code: fields
+                .get("room_code")
+                .and_then(Value::as_str)
+                .filter(|room_code| !room_code.is_empty())
+                .map(ToString::to_string),
+        },
+        None => MessageHeader {
+            message_name: String::new(),
+            ws_client_instance: None,
+            room_code: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM_CODE: &str = "K7QX";
+    const HOST: usize = 11;
+    const GUEST: usize = 22;
+
+    ///a new connection. Nobody reads its messages.
+    fn connect(relay: &SharedRelay, connection_id: usize) {
+        connect_and_listen(relay, connection_id);
+    }
+
+    ///a new connection. The test reads its messages from the receiver.
+    fn connect_and_listen(
+        relay: &SharedRelay,
+        connection_id: usize,
+    ) -> mpsc::UnboundedReceiver<Message> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        relay.lock().expect("error relay lock").connections.insert(
+            connection_id,
+            Connection {
+                ws_client_instance: 0,
+                tx,
+            },
+        );
+        rx
+    }
+
+    ///the message comes from the connection
+    fn send(relay: &SharedRelay, connection_id: usize, ws_message: &WsMessage) {
+        let text = serde_json::to_string(ws_message).expect("error serde_json WsMessage");
+        relay_message(connection_id, &text, relay);
+    }
+
+    ///the host on the connection 1 opens the room of the server referee
+    fn open_room() -> SharedRelay {
+        let relay = SharedRelay::default();
+        connect(&relay, 1);
+        send(
+            &relay,
+            1,
+            &WsMessage::WantToPlay {
+                ws_client_instance: HOST,
+                room_code: ROOM_CODE.to_string(),
+                grid_size: GridSize { rows: 3, cols: 4 },
+                is_server_authoritative: true,
+                content_folder_name: "content".to_string(),
+                player_name: "host".to_string(),
+            },
+        );
+        relay
+    }
+
+    ///the player on a new connection joins the room
+    fn join_room(relay: &SharedRelay, connection_id: usize, ws_client_instance: usize) {
+        connect(relay, connection_id);
+        send(
+            relay,
+            connection_id,
+            &WsMessage::JoinRoom {
+                ws_client_instance,
+                room_code: ROOM_CODE.to_string(),
+                player_name: "guest".to_string(),
+            },
+        );
+    }
+
+    ///the host on the connection 1 started the game with the guest on the connection 2
+    fn started_room() -> SharedRelay {
+        let relay = open_room();
+        join_room(&relay, 2, GUEST);
+        send(
+            &relay,
+            1,
+            &WsMessage::StartGame {
+                ws_client_instance: HOST,
+                room_code: ROOM_CODE.to_string(),
+            },
+        );
+        relay
+    }
+
+    ///all the clicks the server accepted in the room
+    fn count_all_clicks(relay: &SharedRelay) -> usize {
+        let relay = relay.lock().expect("error relay lock");
+        match relay
+            .server_games
+            .get(ROOM_CODE)
+            .and_then(ServerGame::server_state)
+        {
+            Some(WsMessage::ServerState { game_snapshot, .. }) => game_snapshot.count_all_clicks,
+            _ => panic!("the game in the room did not start"),
+        }
+    }
+
+    #[test]
+    fn a_click_under_the_id_of_another_player_is_rejected() {
+        let relay = started_room();
+        //the host is in turn
+        let click_of_host = WsMessage::ServerClick {
+            ws_client_instance: HOST,
+            card_index: 1,
+        };
+        //the guest is bound to its own id
+        send(&relay, 2, &click_of_host);
+        assert_eq!(count_all_clicks(&relay), 0);
+        send(&relay, 1, &click_of_host);
+        assert_eq!(count_all_clicks(&relay), 1);
+    }
+
+    #[test]
+    fn the_same_id_on_a_new_connection_replaces_the_old_one() {
+        let relay = started_room();
+        //the host reconnects after a network drop. The server did not see the old connection close.
+        connect(&relay, 3);
+        send(
+            &relay,
+            3,
+            &WsMessage::Rejoin {
+                ws_client_instance: HOST,
+                other_ws_client_instance: GUEST,
+            },
+        );
+        send(
+            &relay,
+            3,
+            &WsMessage::ServerClick {
+                ws_client_instance: HOST,
+                card_index: 1,
+            },
+        );
+        assert_eq!(count_all_clicks(&relay), 1);
+        //the old connection is gone and its messages are dropped
+        send(
+            &relay,
+            1,
+            &WsMessage::ServerClick {
+                ws_client_instance: HOST,
+                card_index: 2,
+            },
+        );
+        assert_eq!(count_all_clicks(&relay), 1);
+        assert!(!relay
+            .lock()
+            .expect("error relay lock")
+            .connections
+            .contains_key(&1));
+    }
+
+    #[test]
+    fn a_player_that_does_not_fit_does_not_enter_the_room() {
+        let relay = open_room();
+        //the host and the guests fill the room
+        for connection_id in 2..=MAX_PLAYERS {
+            join_room(&relay, connection_id, GUEST + connection_id);
+        }
+        let late_player = GUEST + MAX_PLAYERS + 1;
+        join_room(&relay, MAX_PLAYERS + 1, late_player);
+        let relay = relay.lock().expect("error relay lock");
+        assert_eq!(
+            relay.waiting_server_games[ROOM_CODE].vec_room_players.len(),
+            MAX_PLAYERS
+        );
+        assert_eq!(relay.rooms.len(), MAX_PLAYERS);
+        assert_eq!(relay.rooms.get(&late_player), None);
+    }
+
+    #[test]
+    fn a_huge_grid_size_opens_no_room() {
+        let relay = SharedRelay::default();
+        connect(&relay, 1);
+        send(
+            &relay,
+            1,
+            &WsMessage::WantToPlay {
+                ws_client_instance: HOST,
+                room_code: ROOM_CODE.to_string(),
+                grid_size: GridSize {
+                    rows: usize::MAX,
+                    cols: usize::MAX,
+                },
+                is_server_authoritative: true,
+                content_folder_name: "content".to_string(),
+                player_name: "host".to_string(),
+            },
+        );
+        //the relay is not poisoned and the room is not open
+        let relay = relay.lock().expect("error relay lock");
+        assert!(relay.waiting_server_games.is_empty());
+        assert!(relay.rooms.is_empty());
+    }
+
+    #[test]
+    fn a_game_message_without_a_room_is_dropped() {
+        let relay = started_room();
+        let mut rx = connect_and_listen(&relay, 3);
+        connect(&relay, 4);
+        send(
+            &relay,
+            4,
+            &WsMessage::PlayerClick {
+                ws_client_instance: 44,
+                card_index: 1,
+                count_click_inside_one_turn: 1,
+                card_reveal: None,
+                state_hash: None,
+                seq: 1,
+            },
+        );
+        assert!(rx.try_recv().is_err());
+        //the other messages still go to everybody
+        send(
+            &relay,
+            4,
+            &WsMessage::Hello {
+                ws_client_instance: 44,
+                protocol_version: 1,
+                features: Vec::new(),
+                is_reply: false,
+            },
+        );
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn the_room_is_removed_when_the_last_player_leaves() {
+        let relay = started_room();
+        connection_closed(1, &relay);
+        //the guest is still in the room and the host can reconnect
+        {
+            let relay = relay.lock().expect("error relay lock");
+            assert_eq!(relay.rooms.len(), 2);
+            assert!(relay.server_games.contains_key(ROOM_CODE));
+        }
+        connection_closed(2, &relay);
+        let relay = relay.lock().expect("error relay lock");
+        assert!(relay.rooms.is_empty());
+        assert!(relay.server_games.is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+//! The server authoritative mode: the server owns the deck and the rules engine.
+//! The players send only their clicks. The server validates them, applies them
+//! and sends the resulting game state to all the players of the room, from 2 to `MAX_PLAYERS`.
+//! A click out of turn or on a card that cannot be flipped is a `RuleViolation` and changes nothing.
+//! After the game over the server deals a fresh deck when all the players want the rematch.
+
+//region: use statements
+use mem2_common::gameengine::{DeckError, DeckParameters, GameAction, GameEngine, RuleViolation};
+use mem2_common::{CardPair, GridSize, RoomPlayer, WsMessage};
+//endregion
+
+//region: enum, structs, const,...
+///one game where the server is the referee
+pub struct ServerGame {
+    ///the code of the room
+    room_code: String,
+    ///the rules engine with the deck. Only the server knows all the cards.
+    game_engine: GameEngine,
+    ///the host is player 1, the others in the order they joined
+    vec_room_players: Vec<RoomPlayer>,
+    ///the players that want the rematch after the game over
+    vec_rematch_requests: Vec<usize>,
+}
+//endregion
+
+impl ServerGame {
+    ///new deck for the grid size and the content pack of the room. Player1 starts.
+    pub fn new(
+        room_code: String,
+        grid_size: GridSize,
+        card_count: usize,
+        vec_pairs: Vec<CardPair>,
+        vec_room_players: Vec<RoomPlayer>,
+    ) -> Result<Self, DeckError> {
+        let mut game_engine = GameEngine::new_with_pairs(
+            DeckParameters::new_random(grid_size, card_count),
+            vec_pairs,
+        )?;
+        game_engine.set_player_count(vec_room_players.len());
+        game_engine.start();
+        Ok(ServerGame {
+            room_code,
+            game_engine,
+            vec_room_players,
+            vec_rematch_requests: Vec::new(),
+        })
+    }
+    ///from 1 to the count of players for the players of this game, 0 for everybody else
+    fn player_number(&self, ws_client_instance: usize) -> usize {
+        self.vec_room_players
+            .iter()
+            .position(|room_player| room_player.ws_client_instance == ws_client_instance)
+            .map_or(0, |index| index + 1)
+    }
+    ///only the player in turn can click on a card face down
+    pub fn click(
+        &mut self,
+        ws_client_instance: usize,
+        card_index: usize,
+    ) -> Result<(), RuleViolation> {
+        self.game_engine
+            .validate_click(self.player_number(ws_client_instance), card_index)?;
+        self.game_engine.apply(GameAction::Click { card_index });
+        Ok(())
+    }
+    ///only the next player can take the turn
+    pub fn take_turn(&mut self, ws_client_instance: usize) -> Result<(), RuleViolation> {
+        self.game_engine
+            .validate_take_turn(self.player_number(ws_client_instance))?;
+        self.game_engine.apply(GameAction::TakeTurn);
+        Ok(())
+    }
+    ///the player wants the rematch. When all the players want it, the fresh deck is dealt
+    ///and the player after the one that started the game before starts.
+    ///Returns true if the rematch started.
+    pub fn request_rematch(&mut self, ws_client_instance: usize) -> bool {
+        if !self.game_engine.is_game_over() || self.player_number(ws_client_instance) == 0 {
+            return false;
+        }
+        if !self.vec_rematch_requests.contains(&ws_client_instance) {
+            self.vec_rematch_requests.push(ws_client_instance);
+        }
+        if self.vec_rematch_requests.len() < self.vec_room_players.len() {
+            return false;
+        }
+        let deck_parameters = match &self.game_engine.deck_parameters {
+            Some(deck_parameters) => deck_parameters,
+            None => return false,
+        };
+        let mut game_engine = match GameEngine::new_with_pairs(
+            DeckParameters::new_random(
+                deck_parameters.grid_size,
+                deck_parameters.card_images_count,
+            ),
+            self.game_engine.vec_pairs.clone(),
+        ) {
+            Ok(game_engine) => game_engine,
+            //the deck of the first game was possible, only the random generator of the system can fail
+            Err(_) => return false,
+        };
+        game_engine.set_player_count(self.vec_room_players.len());
+        game_engine.start_with_player(self.game_engine.next_starting_player());
+        self.game_engine = game_engine;
+        self.vec_rematch_requests.clear();
+        true
+    }
+    ///the state for the clients. The face down cards stay secret.
+    ///Only the deck owner has the snapshot and the server built the deck, so it is always there.
+    pub fn server_state(&self) -> Option<WsMessage> {
+        Some(WsMessage::ServerState {
+            room_code: self.room_code.clone(),
+            grid_size: self.game_engine.grid_size,
+            vec_card_commitments: self.game_engine.vec_card_commitments.clone(),
+            game_snapshot: self.game_engine.snapshot()?,
+            vec_room_players: self.vec_room_players.clone(),
+        })
+    }
+}
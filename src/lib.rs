@@ -50,18 +50,30 @@ extern crate web_sys;
 extern crate strum;
 extern crate strum_macros;
 
+mod mem2error;
+
+use crate::mem2error::{js_error_text, Mem2Error, Mem2Result};
 use dodrio::builder::*;
 use dodrio::bumpalo::{self, Bump};
 use dodrio::{Cached, Node, Render};
-//use futures::{future};
+use futures::future::{self, Either};
 use futures::Future;
 use js_sys::Reflect;
+use mem2_common::computerplayer::{ComputerPlayer, MemoryStrength};
+use mem2_common::gameengine::{
+    DeckError, DeckParameters, GameAction, GameEngine, GameEvent, GameResult, RuleViolation,
+};
+use mem2_common::reliablechannel::ReliableChannel;
+use mem2_common::{
+    CardMetadata, CardPair, CardStatusCardFace, ContentPack, GridSize, PackCards, PacksManifest,
+    RoomPlayer, WsMessage, GRID_SIZES, MAX_PLAYERS, PACKS_MANIFEST_PATH,
+};
 use rand::rngs::SmallRng;
-use rand::seq::SliceRandom;
 use rand::FromEntropy;
 use rand::Rng;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{console, WebSocket};
 //Strum is a set of macros and traits for working with enums and strings easier in Rust.
 use strum_macros::AsRefStr;
@@ -78,81 +90,80 @@ use std::rc::Rc;
 //region: enum, structs, const,...
 ///game title
 const GAME_TITLE: &str = "mem2";
-///fixed filename for card face down
-const SRC_FOR_CARD_FACE_DOWN: &str = "img/mem_image_00_cardfacedown.png";
+///version of the WebSocket protocol. Clients with different versions cannot play together.
+const PROTOCOL_VERSION: usize = 9;
+///the first reconnect waits this milliseconds. Every next attempt waits double.
+const RECONNECT_MIN_DELAY_MS: i32 = 500;
+///the longest wait between 2 reconnect attempts
+const RECONNECT_MAX_DELAY_MS: i32 = 16000;
+///the characters of the room code. Without 0, O, 1 and I, that are easy to mix up.
+const ROOM_CODE_CHARS: &str = "ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+///count of characters in the room code
+const ROOM_CODE_LEN: usize = 4;
+///in the solo mode the 2 cards that did not match are face up this milliseconds
+const SOLO_HIDE_DELAY_MS: f64 = 1500.0;
+///the computer opponent waits this milliseconds before every move, so the player can follow it
+const COMPUTER_DELAY_MS: f64 = 1200.0;
+///key for the game in progress in the session storage
+const STORAGE_KEY_GAME: &str = "mem2_game";
+///features of the WebSocket protocol. The other client must have all of them.
+const PROTOCOL_FEATURES: [&str; 10] = [
+    "grid_size",
+    "commit_reveal",
+    "sequence_numbers",
+    "state_hash",
+    "rejoin",
+    "rooms",
+    "server_authoritative",
+    "content_packs",
+    "players",
+    "rematch",
+];
 
 ///Text of game rules.
 ///Multiline string literal just works.
 ///End of line in the code is simply and intuitively end of line in the string.
 ///The special character \ at the end of the line in code means that it is NOT the end of the line for the string.
 ///The escape sequence \n means end of line also.
-const GAME_RULES:& str = "This game is for exactly 2 players. 
+const GAME_RULES:& str = "This game is for 2 players. With the server as referee it is for 2 to 6 players. 
 Both players must have the webpage simultaneously opened in their browsers to allow communication.
-To start over just refresh the webpage.
-The first player clicks on 'Ask Player2 to play?' and broadcasts the message over WebSocket.
-Player2 then sees on the screen 'Click here to Accept play!', clicks it and sends the message back to Player1.
-The game starts with a grid of 8 randomly shuffled card pairs face down - 16 cards in all.
+A game in progress survives the refresh of the webpage. To start over click 'New game'.
+The first player chooses the grid size and clicks on 'Create a room'. The room appears in the lobby of the other players and has a link to share.
+Player2 clicks on the room in the lobby or opens the link and then clicks 'Click here to Accept play!'. A room is only for 2 players.
+With 'Referee: the server' every player that clicks on the room or opens the link joins it, up to 6 players. The first player sees the names and clicks on 'Start the game with N players!'. The players take turns in the order they joined.
+The game starts with a grid of randomly shuffled card pairs face down - for a 4x4 grid 8 pairs, 16 cards in all.
 On the screen under the grid are clear signals which player plays and which waits.
 Player1 flips over two cards with two clicks.
-If the cards do not match, the other player clicks on 'Click here to Take your turn !' and both cards are flipped back face down. Then it is his turn and he clicks to flip over his two cards.
+If the cards do not match, the next player clicks on 'Click here to Take your turn !' and both cards are flipped back face down. Then it is his turn and he clicks to flip over his two cards.
 If the cards match, they are left face up permanently and the player receives a point. He continues to play, he opens the next two cards.
-The player with more points wins.";
+The game is over when all the pairs are found. The player with the most points wins, with equal points it is a draw. The results show the points, the clicks of all players together and the duration. 'Rematch!' deals a fresh deck when all the players want it and the next player starts.
+A player alone clicks on 'Play alone!'. The cards that do not match are flipped back automatically. At the end the game shows the count of clicks and the time.
+Against the computer click on 'Play against the computer!'. The computer is player2 and remembers the flipped cards: easy, medium or with perfect memory.
+Players that share one device click on 'Play on this device!', from 2 to 6 players. After 2 cards that do not match the player passes the device and the next player clicks on the hand-over screen to start the turn.";
 
 ///game description
 const GAME_DESCRIPTION:& str = "Learning to use Rust Wasm/WebAssembly with Dodrio Virtual Dom and WebSockets communication - second iteration. 
 The simple memory game is for kids. 
 The images are funny cartoon characters from the alphabet. 
-The cards grid is only 4x4. 
+The cards grid can be from 2x3 to 6x6. 
 For fun I added the sounds of Morse alphabet codes and 
 show the International Aviation spelling on the screen.";
 
-///Aviation Spelling
-///the zero element is card face down or empty, alphabet begins with 01 : A
-///TODO: read dynamically from json file. Now I know how to do it in javascript, but not in Rust.
-#[derive(Serialize, Deserialize)]
-struct Spelling {
-    ///names of spelling
-    name: Vec<String>,
-}
-
-///`WsMessage` enum for websocket
-#[derive(Serialize, Deserialize)]
-enum WsMessage {
-    ///connection test
-    ConnectionTest {
-        ///anything
-        test: String,
-    },
-    ///want to play
-    WantToPlay {
-        ///ws client instance unique id. To not listen the echo to yourself.
-        ws_client_instance: usize,
-    },
-    /// accept play
-    AcceptPlay {
-        ///ws client instance unique id. To not listen the echo to yourself.
-        ws_client_instance: usize,
-        ///act is the action to take on the receiver
-        card_grid_data: String,
-    },
-    ///player click
-    PlayerClick {
-        ///ws client instance unique id. To not listen the echo to yourself.
-        ws_client_instance: usize,
-        ///card_index
-        card_index: usize,
-        ///count click inside one turn
-        count_click_inside_one_turn: usize,
-    },
-    ///player change
-    PlayerChange {
-        ///ws client instance unique id. To not listen the echo to yourself.
-        ws_client_instance: usize,
+///the state of the WebSocket connection is shown to the player
+enum ConnectionStatus {
+    ///the first connection is opening
+    Connecting,
+    ///the messages can be sent
+    Connected,
+    ///the connection is lost and it waits for the next attempt to connect
+    Reconnecting {
+        ///count of attempts since the connection was lost
+        attempt: u32,
     },
 }
 
 ///the game can be in various states and that differentiate the UI and actions
-#[derive(AsRefStr)]
+#[derive(AsRefStr, Serialize, Deserialize, Clone)]
 enum GameState {
     ///the start of the game
     Start,
@@ -160,30 +171,12 @@ enum GameState {
     Asking,
     ///Player2 is asked WantToPlay
     Asked,
+    ///the player joined the room of the server referee and waits for the host to start the game
+    Joined,
     ///play (the turn is in cardgrid.player_turn)
     Play,
-}
-
-///the 3 possible states of one card
-#[derive(Serialize, Deserialize)]
-enum CardStatusCardFace {
-    ///card face down
-    Down,
-    ///card face Up Temporary
-    UpTemporary,
-    ///card face up Permanently
-    UpPermanently,
-}
-
-///all the data for one card
-#[derive(Serialize, Deserialize)]
-struct Card {
-    ///card status
-    status: CardStatusCardFace,
-    ///field for src attribute for HTML element imagea and filename of card image
-    card_number_and_img_src: usize,
-    ///field for id attribute for HTML element image contains the card index
-    card_index_and_id: usize,
+    ///all pairs are found. The results are shown until the rematch.
+    GameOver,
 }
 
 ///Render Component: player score (cacheable?)
@@ -206,20 +199,8 @@ struct RootRenderingComponent {
 }
 ///game data
 struct GameData {
-    ///vector of cards
-    vec_cards: Vec<Card>,
-    //First turn: Player1 clicks 2 times and opens 2 cards.
-    //If cards match, Player1 receives one point and countinues: 2 click for 2 cards.
-    //If not match: Player2 clicks the Change button to close opened cards.
-    //Then starts the Player2 turn.
-    ///count click inside one turn
-    count_click_inside_one_turn: usize,
-    ///card index of first click
-    card_index_of_first_click: usize,
-    ///card index of second click
-    card_index_of_second_click: usize,
-    ///counts only clicks that flip the card. The third click is not counted.
-    count_all_clicks: usize,
+    ///the rules engine owns the cards, the turn, the click counters and the points
+    game_engine: GameEngine,
     ///web socket. used it to send message onclick.
     ws: WebSocket,
     ///my ws client instance unique id. To not listen the echo to yourself.
@@ -228,16 +209,113 @@ struct GameData {
     other_ws_client_instance: usize,
     ///game state: Start,Asking,Asked,Player1,Player2
     game_state: GameState,
-    ///content folder name
+    ///the content pack with the images, sounds and names of the cards
+    content_pack: ContentPack,
+    ///the content packs to choose from. Empty until content/packs.json is loaded.
+    packs_manifest: PacksManifest,
+    ///What player am I
+    this_machine_player_number: usize,
+    ///the name the player typed. It is sent to the other players.
+    my_player_name: String,
+    ///the names of the players in the order of their player numbers. Empty names are shown as player1...
+    vec_player_names: Vec<String>,
+    ///other ws client instances that said Hello with a compatible protocol
+    vec_compatible_ws_client_instances: Vec<usize>,
+    ///the errors are shown in the error panel until the player dismisses them
+    vec_errors: Vec<Mem2Error>,
+    ///the images, sounds and names of the cards. None while the cards file is loading.
+    pack_cards: Option<PackCards>,
+    ///sequence numbers, acknowledgements and resend for the game messages
+    reliable_channel: ReliableChannel<WsMessage>,
+    ///the state of the WebSocket connection
+    connection_status: ConnectionStatus,
+    ///the room of this game. Empty in the lobby.
+    room_code: String,
+    ///the rooms of other players waiting in the lobby
+    vec_open_rooms: Vec<OpenRoom>,
+    ///the player joined the room, but the server did not yet echo the AcceptPlay.
+    ///If the AcceptPlay of another player comes first, the room was taken.
+    is_room_join_pending: bool,
+    ///the server is the referee: it owns the deck and validates every click.
+    ///The client sends only the clicks and renders the server state.
+    is_server_authoritative: bool,
+    ///one player alone, without the WebSocket handshake
+    is_solo: bool,
+    ///the game started at this time in milliseconds
+    game_start_ms: f64,
+    ///the game ended at this time. 0 while in progress.
+    game_end_ms: f64,
+    ///the players that want the rematch after the game over
+    vec_rematch_requests: Vec<usize>,
+    ///since this time the 2 cards that did not match are face up in the solo mode
+    solo_unmatched_since_ms: f64,
+    ///the computer opponent plays as player 2 in this browser
+    computer_player: Option<ComputerPlayer>,
+    ///the memory strength of the next computer opponent
+    computer_memory_strength: MemoryStrength,
+    ///the computer waits from this time before its next move
+    computer_wait_since_ms: f64,
+    ///the players share this device and take turns on the same screen
+    is_hot_seat: bool,
+    ///the count of players of the next game on this device
+    hot_seat_player_count: usize,
+    ///the device goes to the next player. The cards cannot be clicked until the player is ready.
+    is_hand_over: bool,
+}
+///a room in the lobby with a player waiting for the other player
+struct OpenRoom {
+    ///the code of the room
+    room_code: String,
+    ///the player that created the room
+    ws_client_instance: usize,
+    ///grid size chosen by the player that created the room
+    grid_size: GridSize,
+    ///the server is the referee in this room
+    is_server_authoritative: bool,
+    ///the folder of the content pack of the room
     content_folder_name: String,
+    ///the name of the player that created the room
+    player_name: String,
+}
+
+///the game in progress is saved in the session storage after every move.
+///Everything from GameData that is needed to continue the game after a reload, but not the WebSocket.
+#[derive(Serialize, Deserialize)]
+struct StoredGame {
+    ///the rules engine with the cards, the salts, the turn and the points
+    game_engine: GameEngine,
+    ///the same ws client instance after a reload keeps the pairing with the other player
+    my_ws_client_instance: usize,
+    ///other ws client instance unique id
+    other_ws_client_instance: usize,
+    ///game state
+    game_state: GameState,
     ///What player am I
     this_machine_player_number: usize,
-    ///whose turn is now:  player 1 or 2
-    player_turn: usize,
-    ///player1 points
-    player1_points: usize,
-    ///player2 points
-    player2_points: usize,
+    ///the names of the players
+    vec_player_names: Vec<String>,
+    ///the room of this game
+    room_code: String,
+    ///the sequence numbers continue and the unacknowledged messages are resent
+    reliable_channel: ReliableChannel<WsMessage>,
+    ///the server is the referee
+    is_server_authoritative: bool,
+    ///the content pack of the game
+    content_pack: ContentPack,
+    ///one player alone
+    is_solo: bool,
+    ///the game started at this time in milliseconds
+    #[serde(alias = "solo_start_ms")]
+    game_start_ms: f64,
+    ///the game ended at this time. 0 while in progress.
+    #[serde(alias = "solo_end_ms")]
+    game_end_ms: f64,
+    ///the computer opponent and its memory
+    computer_player: Option<ComputerPlayer>,
+    ///the players share this device
+    is_hot_seat: bool,
+    ///the device goes to the next player
+    is_hand_over: bool,
 }
 //endregion
 
@@ -249,37 +327,63 @@ pub fn run() -> Result<(), JsValue> {
     console_error_panic_hook::set_once();
 
     // Get the document's container to render the virtual dom component.
-    let window = web_sys::window().expect("error: web_sys::window");
+    //without them nothing can be shown, so the error goes to the javascript console
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no web_sys::window"))?;
 
-    let document = window.document().expect("error: window.document");
+    let document = window
+        .document()
+        .ok_or_else(|| JsValue::from_str("no window.document"))?;
     let div_for_virtual_dom = document
         .get_element_by_id("div_for_virtual_dom")
-        .expect("No #div_for_virtual_dom");
-
-    let mut rng = SmallRng::from_entropy();
-    //gen_range is lower inclusive, upper exclusive 26 + 1
-    let my_ws_client_instance: usize = rng.gen_range(1, 9999);
-
-    //find out URL
-    let location_href = window.location().href().expect("href not known");
+        .ok_or_else(|| JsValue::from_str("No #div_for_virtual_dom"))?;
+
+    //the game in progress continues after a reload of the webpage
+    let stored_game = load_game_from_session_storage();
+    let my_ws_client_instance: usize = match &stored_game {
+        Some(stored_game) => stored_game.my_ws_client_instance,
+        None => {
+            let mut rng = SmallRng::from_entropy();
+            //gen_range is lower inclusive, upper exclusive 26 + 1
+            rng.gen_range(1, 9999)
+        }
+    };
 
     //websocket connection
-    let ws = setup_ws_connection(location_href.as_str());
+    let ws = setup_ws_connection().map_err(|error| JsValue::from_str(&error.to_string()))?;
     //I don't know why is needed to clone the websocket connection
     let ws_c = ws.clone();
 
     // Construct a new `RootRenderingComponent`.
     //I added ws_c so that I can send messages on websocket
-    let game_data = GameData::new(ws_c, my_ws_client_instance);
+    let mut game_data = GameData::new(ws_c, my_ws_client_instance);
+    if let Some(stored_game) = stored_game {
+        game_data.restore_stored_game(stored_game);
+    } else {
+        //the link with ?room=CODE joins the room
+        game_data.room_code = room_code_from_url(&window);
+    }
+    let content_pack = game_data.content_pack.clone();
     //TODO: I need to make a Rc<RefCell<GameData>>
     let rc = Rc::new(RefCell::new(game_data));
-    let root_rendering_component = RootRenderingComponent::new(rc);
+    let root_rendering_component =
+        RootRenderingComponent::new(Rc::<std::cell::RefCell<GameData>>::clone(&rc));
 
     // Mount the component to the `<div id="div_for_virtual_dom">`.
     let vdom = dodrio::Vdom::new(&div_for_virtual_dom, root_rendering_component);
 
-    //websocket on receive message callback
-    setup_ws_msg_recv(&ws, &vdom);
+    //websocket on open, on receive message and on close callbacks
+    setup_ws_handlers(&ws, &vdom.weak());
+    //resend the game messages that are not acknowledged
+    if let Err(error) = setup_resend_timer(&vdom) {
+        rc.borrow_mut().show_error(error);
+    }
+    //the solo clock and hiding of the unmatched cards, the moves of the computer
+    if let Err(error) = setup_local_game_timer(&vdom) {
+        rc.borrow_mut().show_error(error);
+    }
+    //the content packs to choose from and the names of the cards
+    fetch_packs_manifest(vdom.weak());
+    fetch_pack_cards(vdom.weak(), &content_pack);
 
     // Run the component forever. Forget to drop the memory.
     vdom.forget();
@@ -302,75 +406,540 @@ fn text_with_br_newline<'a>(txt: &'a str, bump: &'a Bump) -> Vec<Node<'a>> {
 impl GameData {
     ///constructor of game data
     pub fn new(ws: WebSocket, my_ws_client_instance: usize) -> Self {
-        //region: find 8 distinct random numbers between 1 and 26 for the alphabet cards
-        //vec_of_random_numbers is 0 based
-        let mut vec_of_random_numbers = Vec::new();
-        let mut rng = SmallRng::from_entropy();
-        let mut i = 0;
-        while i < 8 {
-            //gen_range is lower inclusive, upper exclusive 26 + 1
-            let num: usize = rng.gen_range(1, 27);
-            if vec_of_random_numbers.contains(&num) {
-                //do nothing if the random number is repeated
-                //debug!("random duplicate {} in {:?}", num, vec_of_random_numbers);
-            } else {
-                //push a pair of the same number
-                vec_of_random_numbers.push(num);
-                vec_of_random_numbers.push(num);
-                i += 1;
-            }
-        }
-        //endregion
-
-        //region: shuffle the numbers
-        let vrndslice = vec_of_random_numbers.as_mut_slice();
-        vrndslice.shuffle(&mut rng);
-        //endregion
-
-        //region: create Cards from random numbers
-        let mut vec_cards = Vec::new();
-
-        //Index 0 is special and reserved for FaceDown. Cards start with base 1
-        let new_card = Card {
-            status: CardStatusCardFace::Down,
-            card_number_and_img_src: 0,
-            card_index_and_id: 0,
-        };
-        vec_cards.push(new_card);
-
-        //create the 16 card and push to the vector
-        for (index, random_number) in vec_of_random_numbers.iter().enumerate() {
-            let new_card = Card {
-                status: CardStatusCardFace::Down,
-                //dereference random number from iterator
-                card_number_and_img_src: *random_number,
-                //card base index will be 1. 0 is reserved for FaceDown.
-                card_index_and_id: index.checked_add(1).expect("usize overflow"),
-            };
-            vec_cards.push(new_card);
-        }
-        //endregion
-
         //return from constructor
         GameData {
-            vec_cards,
-            count_click_inside_one_turn: 0,
-            card_index_of_first_click: 0,
-            card_index_of_second_click: 0,
-            count_all_clicks: 0,
+            //the default grid is possible with the default content. Else the grid stays empty.
+            game_engine: GameEngine::new(DeckParameters::new_random(
+                GridSize::default(),
+                ContentPack::default().card_count,
+            ))
+            .unwrap_or_else(|_| GameEngine::from_commitments(GridSize::default(), Vec::new())),
             ws,
             my_ws_client_instance,
             other_ws_client_instance: 0, //zero means not accepted yet
             game_state: GameState::Start,
-            content_folder_name: "content".to_string(),
-            player1_points: 0,
-            player2_points: 0,
+            content_pack: ContentPack::default(),
+            packs_manifest: PacksManifest::default(),
             this_machine_player_number: 0, //unknown until WantToPlay+Accept
-            player_turn: 0,
+            my_player_name: String::new(),
+            vec_player_names: Vec::new(),
+            vec_compatible_ws_client_instances: Vec::new(),
+            vec_errors: Vec::new(),
+            pack_cards: None,
+            reliable_channel: ReliableChannel::new(),
+            connection_status: ConnectionStatus::Connecting,
+            room_code: String::new(),
+            vec_open_rooms: Vec::new(),
+            is_room_join_pending: false,
+            is_server_authoritative: false,
+            is_solo: false,
+            game_start_ms: 0.0,
+            game_end_ms: 0.0,
+            vec_rematch_requests: Vec::new(),
+            solo_unmatched_since_ms: 0.0,
+            computer_player: None,
+            computer_memory_strength: MemoryStrength::Medium,
+            computer_wait_since_ms: 0.0,
+            is_hot_seat: false,
+            hot_seat_player_count: 2,
+            is_hand_over: false,
+        }
+    }
+    ///continue the game saved in the session storage
+    fn restore_stored_game(&mut self, stored_game: StoredGame) {
+        self.game_engine = stored_game.game_engine;
+        self.my_ws_client_instance = stored_game.my_ws_client_instance;
+        self.other_ws_client_instance = stored_game.other_ws_client_instance;
+        self.game_state = stored_game.game_state;
+        self.this_machine_player_number = stored_game.this_machine_player_number;
+        self.vec_player_names = stored_game.vec_player_names;
+        self.room_code = stored_game.room_code;
+        self.reliable_channel = stored_game.reliable_channel;
+        self.is_server_authoritative = stored_game.is_server_authoritative;
+        self.content_pack = stored_game.content_pack;
+        self.is_solo = stored_game.is_solo;
+        self.game_start_ms = stored_game.game_start_ms;
+        self.game_end_ms = stored_game.game_end_ms;
+        self.computer_player = stored_game.computer_player;
+        self.is_hot_seat = stored_game.is_hot_seat;
+        self.is_hand_over = stored_game.is_hand_over;
+    }
+    ///log the error and show it in the error panel
+    fn show_error(&mut self, error: Mem2Error) {
+        console::log_1(&JsValue::from_str(&error.to_string()));
+        if !self.vec_errors.contains(&error) {
+            self.vec_errors.push(error);
+        }
+    }
+    ///send the message over the WebSocket. The error goes to the error panel.
+    fn send_or_show_error(&mut self, ws_message: &WsMessage) {
+        if let Err(error) = send_ws_message(&self.ws, ws_message) {
+            self.show_error(error);
+        }
+    }
+    ///the metadata of the card in the grid. None while the cards file is loading.
+    fn card_metadata(&self, card_index: usize) -> Option<&CardMetadata> {
+        let card = self.game_engine.vec_cards.get(card_index)?;
+        self.pack_cards.as_ref()?.card(card.card_number_and_img_src)
+    }
+    ///the pairs of different faces of the loaded content pack. Empty for a classic pack.
+    fn pack_pairs(&self) -> Vec<CardPair> {
+        self.pack_cards
+            .as_ref()
+            .map_or_else(Vec::new, PackCards::pairs)
+    }
+    ///the game is played only in this browser: solo, against the computer or on the hot seat
+    fn is_local_game(&self) -> bool {
+        self.is_solo || self.computer_player.is_some() || self.is_hot_seat
+    }
+    ///the name of the player number. Without a typed name it is like player2.
+    fn player_name(&self, player: usize) -> String {
+        player
+            .checked_sub(1)
+            .and_then(|index| self.vec_player_names.get(index))
+            .filter(|player_name| !player_name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("player{}", player))
+    }
+    ///a new deck for the grid size with the pairs of the content pack
+    fn new_deck(&self, grid_size: GridSize) -> Mem2Result<GameEngine> {
+        GameEngine::new_with_pairs(
+            DeckParameters::new_random(grid_size, self.content_pack.card_count),
+            self.pack_pairs(),
+        )
+        .map_err(|error| match error {
+            DeckError::NoSystemRandom => Mem2Error::Browser(error.to_string()),
+            _ => Mem2Error::Content(error.to_string()),
+        })
+    }
+}
+
+///save the game in progress after every move. Only the game in play and its results are saved.
+fn save_game_to_session_storage(game_data: &mut GameData) {
+    if let GameState::Play | GameState::GameOver = game_data.game_state {
+        let stored_game = StoredGame {
+            game_engine: game_data.game_engine.clone(),
+            my_ws_client_instance: game_data.my_ws_client_instance,
+            other_ws_client_instance: game_data.other_ws_client_instance,
+            game_state: game_data.game_state.clone(),
+            this_machine_player_number: game_data.this_machine_player_number,
+            vec_player_names: game_data.vec_player_names.clone(),
+            room_code: game_data.room_code.clone(),
+            reliable_channel: game_data.reliable_channel.clone(),
+            is_server_authoritative: game_data.is_server_authoritative,
+            content_pack: game_data.content_pack.clone(),
+            is_solo: game_data.is_solo,
+            game_start_ms: game_data.game_start_ms,
+            game_end_ms: game_data.game_end_ms,
+            computer_player: game_data.computer_player.clone(),
+            is_hot_seat: game_data.is_hot_seat,
+            is_hand_over: game_data.is_hand_over,
+        };
+        let result = serde_json::to_string(&stored_game)
+            .map_err(|err| Mem2Error::Storage(err.to_string()))
+            .and_then(|json| {
+                session_storage()?
+                    .set_item(STORAGE_KEY_GAME, &json)
+                    .map_err(|err| Mem2Error::Storage(js_error_text(&err)))
+            });
+        if let Err(error) = result {
+            game_data.show_error(error);
+        }
+    }
+}
+
+///the saved game or None. A game saved by a different version of mem2 is discarded.
+fn load_game_from_session_storage() -> Option<StoredGame> {
+    let json = session_storage().ok()?.get_item(STORAGE_KEY_GAME).ok()??;
+    let stored_game = serde_json::from_str(&json).ok();
+    if stored_game.is_none() {
+        console::log_1(&"the saved game cannot be restored".into());
+        //the error is not important, the game starts from the beginning anyway
+        let _ = remove_game_from_session_storage();
+    }
+    stored_game
+}
+
+///the saved game is discarded for a new game
+fn remove_game_from_session_storage() -> Mem2Result<()> {
+    session_storage()?
+        .remove_item(STORAGE_KEY_GAME)
+        .map_err(|err| Mem2Error::Storage(js_error_text(&err)))
+}
+
+///random room code like K7QX
+fn generate_room_code() -> String {
+    let mut rng = SmallRng::from_entropy();
+    let vec_chars: Vec<char> = ROOM_CODE_CHARS.chars().collect();
+    (0..ROOM_CODE_LEN)
+        .map(|_| vec_chars[rng.gen_range(0, vec_chars.len())])
+        .collect()
+}
+
+///the room code from the link ?room=CODE. Empty if there is none.
+fn room_code_from_url(window: &web_sys::Window) -> String {
+    let search = window.location().search().unwrap_or_default();
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|param| {
+            if param.starts_with("room=") {
+                Some(param.trim_start_matches("room="))
+            } else {
+                None
+            }
+        })
+        .unwrap_or("")
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(ROOM_CODE_LEN)
+        .collect::<String>()
+        .to_uppercase()
+}
+
+///the link to share with the other player
+fn room_link(room_code: &str) -> Mem2Result<String> {
+    let location = browser_window()?.location();
+    Ok(format!(
+        "{}{}?room={}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default(),
+        room_code
+    ))
+}
+
+///the room is open. The player that created it sends it again to every new client.
+fn send_want_to_play(game_data: &mut GameData) {
+    let ws_message = WsMessage::WantToPlay {
+        ws_client_instance: game_data.my_ws_client_instance,
+        room_code: game_data.room_code.clone(),
+        grid_size: game_data.game_engine.grid_size,
+        is_server_authoritative: game_data.is_server_authoritative,
+        content_folder_name: game_data.content_pack.folder.clone(),
+        player_name: game_data.my_player_name.clone(),
+    };
+    game_data.send_or_show_error(&ws_message);
+}
+
+///the player joins the room of the server referee. The host starts the game when all players joined.
+fn join_room(game_data: &mut GameData) {
+    game_data.this_machine_player_number = 0;
+    game_data.game_state = GameState::Joined;
+    let ws_message = WsMessage::JoinRoom {
+        ws_client_instance: game_data.my_ws_client_instance,
+        room_code: game_data.room_code.clone(),
+        player_name: game_data.my_player_name.clone(),
+    };
+    game_data.send_or_show_error(&ws_message);
+}
+
+///the host starts the game with the players that joined. The server deals the cards.
+fn start_game(game_data: &mut GameData) {
+    let ws_message = WsMessage::StartGame {
+        ws_client_instance: game_data.my_ws_client_instance,
+        room_code: game_data.room_code.clone(),
+    };
+    send_to_server(game_data, &ws_message);
+}
+
+///the clock of the new game or of the rematch starts
+fn start_clock(game_data: &mut GameData) {
+    game_data.game_start_ms = js_sys::Date::now();
+    game_data.game_end_ms = 0.0;
+    game_data.vec_rematch_requests.clear();
+}
+
+///the game is over when all pairs are found. The clock stops.
+fn check_game_over(game_data: &mut GameData) {
+    if let GameState::Play = game_data.game_state {
+        if game_data.game_engine.is_game_over() {
+            game_data.game_state = GameState::GameOver;
+            if game_data.game_end_ms == 0.0 {
+                game_data.game_end_ms = js_sys::Date::now();
+            }
+            save_game_to_session_storage(game_data);
+        }
+    }
+}
+
+///the player wants the rematch. The local game starts it at once,
+///the network game asks the other players.
+fn request_rematch(game_data: &mut GameData) -> Mem2Result<()> {
+    if game_data.is_local_game() {
+        return local_rematch(game_data);
+    }
+    let my_ws_client_instance = game_data.my_ws_client_instance;
+    if game_data
+        .vec_rematch_requests
+        .contains(&my_ws_client_instance)
+    {
+        return Ok(());
+    }
+    game_data.vec_rematch_requests.push(my_ws_client_instance);
+    if game_data.is_server_authoritative {
+        send_server_rematch_request(game_data);
+        return Ok(());
+    }
+    //the request goes in the game stream, so the other player gets it after the last move
+    let room_code = game_data.room_code.clone();
+    send_game_message(game_data, |seq| WsMessage::RematchRequest {
+        ws_client_instance: my_ws_client_instance,
+        room_code,
+        seq,
+    });
+    deal_rematch_if_both_want_it(game_data)?;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the server deals the rematch when all the players of the room want it
+fn send_server_rematch_request(game_data: &mut GameData) {
+    let ws_message = WsMessage::ServerRematchRequest {
+        ws_client_instance: game_data.my_ws_client_instance,
+    };
+    send_to_server(game_data, &ws_message);
+}
+
+///between 2 browsers the deck owner deals the fresh deck, when both players want it
+fn deal_rematch_if_both_want_it(game_data: &mut GameData) -> Mem2Result<()> {
+    if game_data.game_engine.deck_parameters.is_some()
+        && game_data
+            .vec_rematch_requests
+            .contains(&game_data.my_ws_client_instance)
+        && game_data
+            .vec_rematch_requests
+            .contains(&game_data.other_ws_client_instance)
+    {
+        deal_rematch(game_data)?;
+    }
+    Ok(())
+}
+
+///a fresh deck in this browser. The next player starts.
+fn local_rematch(game_data: &mut GameData) -> Mem2Result<()> {
+    let player_count = game_data.game_engine.player_count;
+    let starting_player = game_data.game_engine.next_starting_player();
+    game_data.game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.game_engine.set_player_count(player_count);
+    game_data.game_engine.start_with_player(starting_player);
+    log_deck_seed(game_data);
+    if let Some(computer_player) = game_data.computer_player.as_mut() {
+        //the computer forgets the old deck
+        *computer_player = ComputerPlayer::new(computer_player.memory_strength);
+        game_data.computer_wait_since_ms = js_sys::Date::now();
+    }
+    if game_data.is_hot_seat {
+        //the device goes to the player that starts
+        game_data.this_machine_player_number = starting_player;
+        game_data.is_hand_over = true;
+    }
+    start_clock(game_data);
+    game_data.game_state = GameState::Play;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the deck owner deals a fresh deck when both players want the rematch.
+///It sends only the commitments. The other player starts.
+///The game stream goes on, so the moves of the rematch come after the fresh deck.
+fn deal_rematch(game_data: &mut GameData) -> Mem2Result<()> {
+    let starting_player = game_data.game_engine.next_starting_player();
+    game_data.game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.game_engine.start_with_player(starting_player);
+    log_deck_seed(game_data);
+    let ws_client_instance = game_data.my_ws_client_instance;
+    let room_code = game_data.room_code.clone();
+    let grid_size = game_data.game_engine.grid_size;
+    let vec_card_commitments = game_data.game_engine.vec_card_commitments.clone();
+    send_game_message(game_data, |seq| WsMessage::Rematch {
+        ws_client_instance,
+        room_code,
+        grid_size,
+        vec_card_commitments,
+        starting_player,
+        seq,
+    });
+    start_clock(game_data);
+    game_data.game_state = GameState::Play;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the solo game starts without the other player and without the WebSocket
+fn start_solo(game_data: &mut GameData) -> Mem2Result<()> {
+    let game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.is_solo = true;
+    //the referee is the engine in this browser
+    game_data.is_server_authoritative = false;
+    game_data.this_machine_player_number = 1;
+    game_data.vec_player_names = vec![game_data.my_player_name.clone()];
+    game_data.game_engine = game_engine;
+    game_data.game_engine.set_player_count(1);
+    game_data.game_engine.start();
+    log_deck_seed(game_data);
+    start_clock(game_data);
+    game_data.game_state = GameState::Play;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the game against the computer starts without the other player and without the WebSocket
+fn start_vs_computer(game_data: &mut GameData) -> Mem2Result<()> {
+    let game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.is_solo = false;
+    //the referee is the engine in this browser
+    game_data.is_server_authoritative = false;
+    game_data.this_machine_player_number = 1;
+    game_data.vec_player_names = vec![game_data.my_player_name.clone(), "computer".to_string()];
+    game_data.game_engine = game_engine;
+    game_data.game_engine.start();
+    log_deck_seed(game_data);
+    game_data.computer_player = Some(ComputerPlayer::new(game_data.computer_memory_strength));
+    game_data.computer_wait_since_ms = js_sys::Date::now();
+    start_clock(game_data);
+    game_data.game_state = GameState::Play;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the players of the hot seat share this device. The first player has the typed name.
+fn start_hot_seat(game_data: &mut GameData) -> Mem2Result<()> {
+    let game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.is_solo = false;
+    game_data.is_hot_seat = true;
+    //the referee is the engine in this browser
+    game_data.is_server_authoritative = false;
+    game_data.vec_player_names = vec![game_data.my_player_name.clone()];
+    game_data.game_engine = game_engine;
+    game_data
+        .game_engine
+        .set_player_count(game_data.hot_seat_player_count);
+    game_data.game_engine.start();
+    log_deck_seed(game_data);
+    //this device is always the player in turn
+    game_data.this_machine_player_number = 1;
+    game_data.is_hand_over = false;
+    start_clock(game_data);
+    game_data.game_state = GameState::Play;
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
+///the next player of the hot seat takes the turn. The device goes to that player.
+fn hot_seat_take_turn(game_data: &mut GameData) {
+    let vec_events = game_data.game_engine.apply(GameAction::TakeTurn);
+    if !vec_events.is_empty() {
+        game_data.this_machine_player_number = game_data.game_engine.player_turn;
+        game_data.is_hand_over = true;
+        save_game_to_session_storage(game_data);
+    }
+}
+
+///the local game reacts to the click without sending anything
+fn local_game_after_click(game_data: &mut GameData, vec_events: &[GameEvent]) {
+    if game_data.is_hot_seat {
+        //the next player takes the turn on the hand-over screen
+        return;
+    }
+    if game_data.computer_player.is_some() {
+        //the computer sees the cards of the player too
+        computer_observe(game_data, vec_events);
+        game_data.computer_wait_since_ms = js_sys::Date::now();
+        return;
+    }
+    //the solo game hides the 2 cards after a delay
+    if vec_events.contains(&GameEvent::PairNotMatched) {
+        game_data.solo_unmatched_since_ms = js_sys::Date::now();
+    }
+}
+
+///the computer maybe remembers the revealed cards
+fn computer_observe(game_data: &mut GameData, vec_events: &[GameEvent]) {
+    let mut rng = SmallRng::from_entropy();
+    if let Some(computer_player) = game_data.computer_player.as_mut() {
+        for game_event in vec_events {
+            if let GameEvent::CardRevealed { card_index } = game_event {
+                computer_player.observe(&game_data.game_engine, *card_index, &mut rng);
+            }
         }
     }
 }
 
+///the next move of the computer after the delay: a click in its turn
+///or the take turn after the 2 cards of the player did not match.
+///Returns the events of the move, empty if there was no move.
+fn computer_move(game_data: &mut GameData) -> Vec<GameEvent> {
+    let now = js_sys::Date::now();
+    let game_engine = &game_data.game_engine;
+    if game_engine.is_game_over() || now - game_data.computer_wait_since_ms < COMPUTER_DELAY_MS {
+        return Vec::new();
+    }
+    let game_action = if game_engine.player_turn == 2 {
+        let mut rng = SmallRng::from_entropy();
+        match game_data
+            .computer_player
+            .as_ref()
+            .and_then(|computer_player| computer_player.choose_click(game_engine, &mut rng))
+        {
+            Some(card_index) => GameAction::Click { card_index },
+            //the player takes the turn after the 2 cards of the computer did not match
+            None => return Vec::new(),
+        }
+    } else if game_engine.count_click_inside_one_turn >= 2
+        && game_engine.is_pair_revealed()
+        && !game_engine.is_pair_matched()
+    {
+        GameAction::TakeTurn
+    } else {
+        return Vec::new();
+    };
+    let vec_events = game_data.game_engine.apply(game_action);
+    computer_observe(game_data, &vec_events);
+    game_data.computer_wait_since_ms = now;
+    save_game_to_session_storage(game_data);
+    vec_events
+}
+
+///minutes and seconds like 2:05
+fn elapsed_text(elapsed_ms: f64) -> String {
+    let seconds = (elapsed_ms / 1000.0).max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+///Player2 accepts to play in the room of the other player.
+///Player2 is the deck owner and sends only the commitments.
+///If the server is the referee, the player joins the room instead.
+fn accept_play(game_data: &mut GameData) -> Mem2Result<()> {
+    //the deck with the pairs of the loaded content pack
+    let game_engine = game_data.new_deck(game_data.game_engine.grid_size)?;
+    game_data.this_machine_player_number = 2;
+    //the name of the player that created the room is the first
+    game_data.vec_player_names.truncate(1);
+    let my_player_name = game_data.my_player_name.clone();
+    game_data.vec_player_names.push(my_player_name);
+    game_data.reliable_channel = ReliableChannel::new();
+    game_data.game_state = GameState::Play;
+    start_clock(game_data);
+    game_data.is_room_join_pending = true;
+    game_data.game_engine = game_engine;
+    game_data.game_engine.start();
+    log_deck_seed(game_data);
+    let vec_card_commitments = game_data.game_engine.vec_card_commitments.clone();
+
+    let ws_message = WsMessage::AcceptPlay {
+        ws_client_instance: game_data.my_ws_client_instance,
+        room_code: game_data.room_code.clone(),
+        grid_size: game_data.game_engine.grid_size,
+        //send only the commitments, so the other player cannot see the cards.
+        vec_card_commitments,
+        content_folder_name: game_data.content_pack.folder.clone(),
+        player_name: game_data.my_player_name.clone(),
+    };
+    game_data.send_or_show_error(&ws_message);
+    save_game_to_session_storage(game_data);
+    Ok(())
+}
+
 //region:CardGrid struct is the only persistant data we have in Rust Virtual Dom.dodrio
 //in the constructor we initialize that data.
 //Later onclick we change this data.
@@ -392,126 +961,227 @@ impl RootRenderingComponent {
             cached_rules_and_description,
         }
     }
-    ///The onclick event passed by javascript executes all the logic
-    ///and changes only the fields of the Card Grid struct.
-    ///That stuct is the only permanent data storage for later render the virtual dom.
-    fn card_on_click(&self, mut game_data: std::cell::RefMut<'_, GameData>) {
-        //get this_click_card_index from game_data
-        let this_click_card_index = if game_data.count_click_inside_one_turn == 1 {
-            game_data.card_index_of_first_click
-        } else {
-            game_data.card_index_of_second_click
-        };
+    ///The rules engine changes the game data. Here we only react to the events it returns.
+    ///The sound is played for both players, when the card value is revealed.
+    fn react_to_game_events(&self, game_data: &mut GameData, vec_events: &[GameEvent]) {
+        for game_event in vec_events {
+            if let GameEvent::RevealRejected { card_index } = game_event {
+                console::log_1(&JsValue::from_str(&format!(
+                    "the revealed card {} does not match the commitment",
+                    card_index
+                )));
+            } else if let GameEvent::CardRevealed { card_index } = game_event {
+                if let Err(error) = play_card_sound(game_data, *card_index) {
+                    game_data.show_error(error);
+                }
+            }
+        }
+        check_game_over(game_data);
+    }
+}
+//endregion
 
-        if game_data.count_click_inside_one_turn == 1 || game_data.count_click_inside_one_turn == 2
-        {
-            //region: audio play
-            //prepare the audio element with src filename of mp3
-            let audio_element = web_sys::HtmlAudioElement::new_with_src(
-                format!(
-                    "{}/sound/mem_sound_{:02}.mp3",
-                    game_data.content_folder_name,
-                    game_data
-                        .vec_cards
-                        .get(this_click_card_index)
-                        .expect("error this_click_card_index")
-                        .card_number_and_img_src
-                )
-                .as_str(),
-            );
+///the deck seed is all we need to reproduce a game for bug reports.
+///Only the deck owner knows it.
+fn log_deck_seed(game_data: &GameData) {
+    if let Some(deck_parameters) = &game_data.game_engine.deck_parameters {
+        console::log_1(&JsValue::from_str(&format!(
+            "deck seed: {}",
+            deck_parameters.seed
+        )));
+    }
+}
 
-            //play() return a Promise in JSValue. That is too hard for me to deal with now.
-            audio_element
-                .expect("Error: HtmlAudioElement new.")
-                .play()
-                .expect("Error: HtmlAudioElement.play() ");
-            //endregion
-
-            //flip the card up
-            game_data
-                .vec_cards
-                .get_mut(this_click_card_index)
-                .expect("error this_click_card_index")
-                .status = CardStatusCardFace::UpTemporary;
-
-            if game_data.count_click_inside_one_turn == 2 {
-                //if is the second click, flip the card and then check for card match
-
-                //if the cards match, player get one point and continues another turn
-                if game_data
-                    .vec_cards
-                    .get(game_data.card_index_of_first_click)
-                    .expect("error game_data.card_index_of_first_click")
-                    .card_number_and_img_src
-                    == game_data
-                        .vec_cards
-                        .get(game_data.card_index_of_second_click)
-                        .expect("error game_data.card_index_of_second_click")
-                        .card_number_and_img_src
-                {
-                    //give points
-                    if game_data.player_turn == 1 {
-                        game_data.player1_points += 1;
-                    } else {
-                        game_data.player2_points += 1;
+///play the sound of the revealed card.
+///play() returns a Promise. The game does not wait for the sound.
+fn play_card_sound(game_data: &GameData, card_index: usize) -> Mem2Result<()> {
+    let card_metadata = game_data
+        .card_metadata(card_index)
+        .ok_or_else(|| Mem2Error::Content(format!("there is no card {}", card_index)))?;
+    //some cards are silent
+    let sound = match &card_metadata.sound {
+        Some(sound) => sound,
+        None => return Ok(()),
+    };
+    //prepare the audio element with src filename of mp3
+    let src = format!("{}/{}", game_data.content_pack.folder, sound);
+    let audio_element = web_sys::HtmlAudioElement::new_with_src(&src)
+        .map_err(|err| Mem2Error::Content(format!("{}: {}", src, js_error_text(&err))))?;
+    let _promise = audio_element
+        .play()
+        .map_err(|err| Mem2Error::Content(format!("{}: {}", src, js_error_text(&err))))?;
+    Ok(())
+}
+
+///fetch the cards file from the content folder and keep the parsed PackCards in the game data
+fn fetch_pack_cards(weak: dodrio::VdomWeak, content_pack: &ContentPack) {
+    let url = format!("{}/{}", content_pack.folder, content_pack.cards_file);
+    let card_count = content_pack.card_count;
+    let folder = content_pack.folder.clone();
+    wasm_bindgen_futures::spawn_local(fetch_text(&url).then(move |result| {
+        weak.with_component({
+            let v2 = weak.clone();
+            move |root| {
+                let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                //this game_data mutable reference is dropped on the end of the function
+                let mut game_data = root_rendering_component.rc.borrow_mut();
+                //the player can choose another pack while this one is loading
+                if game_data.content_pack.folder != folder {
+                    return;
+                }
+                let pack_cards = result
+                    .and_then(|text| {
+                        serde_json::from_str::<PackCards>(&text).map_err(|err| {
+                            Mem2Error::Content(format!("{} cannot be parsed: {}", url, err))
+                        })
+                    })
+                    .and_then(|pack_cards| {
+                        //the grid sizes are chosen with the card count from the manifest
+                        if pack_cards.pair_count() < card_count {
+                            Err(Mem2Error::Content(format!(
+                                "{} has {} pairs instead of {}",
+                                url,
+                                pack_cards.pair_count(),
+                                card_count
+                            )))
+                        } else {
+                            Ok(pack_cards)
+                        }
+                    });
+                match pack_cards {
+                    Ok(pack_cards) => {
+                        //the deck from the commitments does not know the pairs of the pack
+                        if game_data.game_engine.deck_parameters.is_none() {
+                            game_data.game_engine.vec_pairs = pack_cards.pairs();
+                        }
+                        game_data.pack_cards = Some(pack_cards);
                     }
+                    Err(error) => game_data.show_error(error),
+                }
+                v2.schedule_render();
+            }
+        })
+        .map_err(|_| ())
+    }));
+}
 
-                    // the two cards matches. make them permanent FaceUp
-                    let x1 = game_data.card_index_of_first_click;
-                    let x2 = game_data.card_index_of_second_click;
-                    game_data
-                        .vec_cards
-                        .get_mut(x1)
-                        .expect("error game_data.card_index_of_first_click")
-                        .status = CardStatusCardFace::UpPermanently;
-                    game_data
-                        .vec_cards
-                        .get_mut(x2)
-                        .expect("error game_data.card_index_of_second_click")
-                        .status = CardStatusCardFace::UpPermanently;
-                    game_data.count_click_inside_one_turn = 0;
+///fetch the manifest content/packs.json with the content packs to choose from
+fn fetch_packs_manifest(weak: dodrio::VdomWeak) {
+    wasm_bindgen_futures::spawn_local(fetch_text(PACKS_MANIFEST_PATH).then(move |result| {
+        weak.with_component({
+            let v2 = weak.clone();
+            move |root| {
+                let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                //this game_data mutable reference is dropped on the end of the function
+                let mut game_data = root_rendering_component.rc.borrow_mut();
+                let packs_manifest = result.and_then(|text| {
+                    serde_json::from_str::<PacksManifest>(&text).map_err(|err| {
+                        Mem2Error::Content(format!(
+                            "{} cannot be parsed: {}",
+                            PACKS_MANIFEST_PATH, err
+                        ))
+                    })
+                });
+                match packs_manifest {
+                    Ok(packs_manifest) => {
+                        //the manifest has the last word about the pack in use
+                        if let Some(content_pack) =
+                            packs_manifest.find(&game_data.content_pack.folder)
+                        {
+                            game_data.content_pack = content_pack.clone();
+                        }
+                        game_data.packs_manifest = packs_manifest;
+                    }
+                    Err(error) => game_data.show_error(error),
                 }
+                v2.schedule_render();
             }
-        }
-    }
-    ///fn on change for both click and we msg.
-    fn take_turn(&self, mut game_data: std::cell::RefMut<'_, GameData>) {
-        game_data.player_turn = if game_data.player_turn == 1 { 2 } else { 1 };
-
-        //click on Change button closes first and second card
-        let x1 = game_data.card_index_of_first_click;
-        let x2 = game_data.card_index_of_second_click;
-        game_data
-            .vec_cards
-            .get_mut(x1)
-            .expect("error game_data.card_index_of_first_click ")
-            .status = CardStatusCardFace::Down;
-        game_data
-            .vec_cards
-            .get_mut(x2)
-            .expect("error game_data.card_index_of_second_click")
-            .status = CardStatusCardFace::Down;
-        game_data.card_index_of_first_click = 0;
-        game_data.card_index_of_second_click = 0;
-        game_data.count_click_inside_one_turn = 0;
+        })
+        .map_err(|_| ())
+    }));
+}
+
+///fetch a text file from the web server
+fn fetch_text(url: &str) -> impl Future<Item = String, Error = Mem2Error> {
+    let window = match browser_window() {
+        Ok(window) => window,
+        Err(error) => return Either::A(future::err(error)),
+    };
+    let url_for_error = url.to_string();
+    let future_text = JsFuture::from(window.fetch_with_str(url))
+        .and_then(|response| {
+            let response: web_sys::Response = response.dyn_into()?;
+            if !response.ok() {
+                return Err(JsValue::from_str(&format!(
+                    "HTTP error, status = {}",
+                    response.status()
+                )));
+            }
+            response.text()
+        })
+        .and_then(JsFuture::from)
+        .map(|text| text.as_string().unwrap_or_default())
+        .map_err(move |err| {
+            Mem2Error::Content(format!("{}: {}", url_for_error, js_error_text(&err)))
+        });
+    Either::B(future_text)
+}
+
+///use the content pack from the manifest and load the names of its cards
+fn choose_content_pack(
+    game_data: &mut GameData,
+    weak: &dodrio::VdomWeak,
+    content_folder_name: &str,
+) -> Mem2Result<()> {
+    if game_data.content_pack.folder == content_folder_name {
+        return Ok(());
     }
-    ///get spelling from session storage
-    fn get_spelling(&self) -> Spelling {
-        let x: Option<Spelling> = session_storage()
-            .get("Spelling")
-            .ok()
-            .and_then(|opt| opt)
-            .and_then(|json| serde_json::from_str(&json).ok());
+    let content_pack = game_data
+        .packs_manifest
+        .find(content_folder_name)
+        .cloned()
+        .ok_or_else(|| {
+            Mem2Error::Content(format!(
+                "the content pack {} is unknown",
+                content_folder_name
+            ))
+        })?;
+    game_data.content_pack = content_pack;
+    game_data.pack_cards = None;
+    fetch_pack_cards(weak.clone(), &game_data.content_pack);
+    Ok(())
+}
 
-        x.expect("error session_storage().get('Spelling')")
+///the content pack and a new deck for the room of the other player
+fn join_content_pack(
+    game_data: &mut GameData,
+    weak: &dodrio::VdomWeak,
+    content_folder_name: &str,
+    grid_size: GridSize,
+) -> Mem2Result<()> {
+    choose_content_pack(game_data, weak, content_folder_name)?;
+    let card_count = game_data.content_pack.card_count;
+    if !grid_size.is_possible(card_count) {
+        return Err(Mem2Error::Content(format!(
+            "the content pack {} has not enough pairs for {}x{}",
+            content_folder_name, grid_size.rows, grid_size.cols
+        )));
     }
+    game_data.game_engine = game_data.new_deck(grid_size)?;
+    Ok(())
+}
+
+///the browser window. Only outside of the browser there is none.
+fn browser_window() -> Mem2Result<web_sys::Window> {
+    web_sys::window().ok_or_else(|| Mem2Error::Browser("the window is not available".to_string()))
 }
-//endregion
 
 /// Get the top-level window's session storage.
-pub fn session_storage() -> web_sys::Storage {
-    let window = web_sys::window().expect("error: web_sys::window");
-    window.session_storage().unwrap_throw().unwrap_throw()
+pub fn session_storage() -> Mem2Result<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|window| window.session_storage().ok())
+        .and_then(|storage| storage)
+        .ok_or_else(|| Mem2Error::Storage("the session storage is not available".to_string()))
 }
 
 //region: `Render` trait implementation on CardGrid struct
@@ -548,35 +1218,58 @@ impl Render for RootRenderingComponent {
             let game_data = cr_gr.rc.borrow();
 
             let mut vec_grid_item_bump = Vec::new();
-            for x in 1..=16 {
-                let index: usize = x;
+            //the card with index 0 is reserved for FaceDown
+            for card in game_data.game_engine.vec_cards.iter().skip(1) {
+                let index = card.card_index_and_id;
                 //region: prepare variables and closures for inserting into vdom
-                let img_src = match game_data.vec_cards.get(index).expect("error index").status {
+                let img_src = match card.status {
+                    //a card face up, but not yet revealed by the deck owner, stays face down
+                    CardStatusCardFace::UpTemporary
+                        if !game_data.game_engine.is_card_revealed(index) =>
+                    {
+                        bumpalo::format!(in bump, "{}/{}",
+                                                game_data.content_pack.folder,
+                                                game_data.content_pack.card_back)
+                        .into_bump_str()
+                    }
                     CardStatusCardFace::Down => bumpalo::format!(in bump, "{}/{}",
-                                                game_data.content_folder_name,
-                                                SRC_FOR_CARD_FACE_DOWN)
+                                                game_data.content_pack.folder,
+                                                game_data.content_pack.card_back)
                     .into_bump_str(),
                     CardStatusCardFace::UpTemporary | CardStatusCardFace::UpPermanently => {
-                        bumpalo::format!(in bump, "{}/img/mem_image_{:02}.png",
-                        game_data.content_folder_name,
-                                game_data
-                                    .vec_cards
-                                    .get(index)
-                                    .expect("error index")
-                                    .card_number_and_img_src
+                        //the card back until the cards file is loaded
+                        bumpalo::format!(in bump, "{}/{}",
+                        game_data.content_pack.folder,
+                        game_data.card_metadata(index)
+                            .map_or(game_data.content_pack.card_back.as_str(), |card_metadata| {
+                                card_metadata.image.as_str()
+                            })
                         )
                         .into_bump_str()
                     }
                 };
+                //the alt text and the tooltip only for the revealed card face
+                let (img_alt, img_title) = match game_data.card_metadata(index) {
+                    Some(card_metadata)
+                        if !img_src.ends_with(&game_data.content_pack.card_back) =>
+                    {
+                        (
+                            bumpalo::format!(in bump, "{}", card_metadata.alt).into_bump_str(),
+                            bumpalo::format!(in bump, "{}",
+                                card_metadata.description.as_ref().map_or("", String::as_str)
+                            )
+                            .into_bump_str(),
+                        )
+                    }
+                    _ => ("card face down", ""),
+                };
 
-                let img_id =
-                    bumpalo::format!(in bump, "img{:02}",game_data.vec_cards.get(index).expect("error index").card_index_and_id)
-                        .into_bump_str();
+                let img_id = bumpalo::format!(in bump, "img{:02}", index).into_bump_str();
 
                 let opacity = if img_src
                     == format!(
                         "{}/{}",
-                        game_data.content_folder_name, SRC_FOR_CARD_FACE_DOWN
+                        game_data.content_pack.folder, game_data.content_pack.card_back
                     ) {
                     bumpalo::format!(in bump, "opacity:{}", 0.2).into_bump_str()
                 } else {
@@ -584,11 +1277,13 @@ impl Render for RootRenderingComponent {
                 };
                 //endregion
 
-                //creating 16 <div> in loop
+                //creating a <div> for every card in loop
                 let grid_item_bump = div(bump)
                     .attr("class", "grid_item")
                     .children([img(bump)
                         .attr("src", img_src)
+                        .attr("alt", img_alt)
+                        .attr("title", img_title)
                         .attr("id", img_id)
                         .attr("style", opacity)
                         //on click needs a code Closure in Rust. Dodrio and wasm-bindgen
@@ -603,12 +1298,11 @@ impl Render for RootRenderingComponent {
                             //this game_data mutable reference is dropped on the end of the function
                             let mut game_data = root_rendering_component.rc.borrow_mut();
                             //the click on grid is allowed only when is the turn of this player
-                            if (game_data.game_state.as_ref() == GameState::Play.as_ref()
-                                && game_data.player_turn == 1
-                                && game_data.this_machine_player_number == 1)
-                                || (game_data.game_state.as_ref() == GameState::Play.as_ref()
-                                    && game_data.player_turn == 2
-                                    && game_data.this_machine_player_number == 2)
+                            if game_data.game_state.as_ref() == GameState::Play.as_ref()
+                                && game_data.game_engine.player_turn != 0
+                                && game_data.game_engine.player_turn
+                                    == game_data.this_machine_player_number
+                                && !game_data.is_hand_over
                             {
                                 // If the event's target is our image...
                                 let img = match event
@@ -621,51 +1315,61 @@ impl Render for RootRenderingComponent {
                                 };
 
                                 //id attribute of image html element is prefixed with img ex. "img12"
-                                let this_click_card_index =
-                                    (img.id().get(3..).expect("error slicing"))
-                                        .parse::<usize>()
-                                        .expect("error parse img id to usize");
-
-                                //click is usefull only od facedown cards
-                                if let CardStatusCardFace::Down = game_data
-                                    .vec_cards
-                                    .get(this_click_card_index)
-                                    .expect("error this_click_card_index")
-                                    .status
+                                let this_click_card_index = match img
+                                    .id()
+                                    .get(3..)
+                                    .and_then(|card_index| card_index.parse::<usize>().ok())
                                 {
-                                    //the begining of the turn is count_click_inside_one_turn=0
-                                    //on click imediately increase that. So first click is 1 and second click is 2.
-                                    //all other clicks on the grid are not usable.
-                                    game_data.count_click_inside_one_turn += 1;
-
-                                    if game_data.count_click_inside_one_turn == 1 {
-                                        game_data.card_index_of_first_click = this_click_card_index;
-                                        game_data.card_index_of_second_click = 0;
-                                        game_data.count_all_clicks += 1;
-                                    } else if game_data.count_click_inside_one_turn == 2 {
-                                        game_data.card_index_of_second_click =
-                                            this_click_card_index;
-                                        game_data.count_all_clicks += 1;
-                                    } else {
-                                        //nothing
-                                    }
+                                    None => return,
+                                    Some(card_index) => card_index,
+                                };
 
-                                    //region: send WsMessage over websocket
-                                    game_data
-                                        .ws
-                                        .send_with_str(
-                                            &serde_json::to_string(&WsMessage::PlayerClick {
-                                                ws_client_instance: game_data.my_ws_client_instance,
-                                                card_index: this_click_card_index,
-                                                count_click_inside_one_turn: game_data
-                                                    .count_click_inside_one_turn,
-                                            })
-                                            .expect("error sending PlayerClick"),
-                                        )
-                                        .expect("Failed to send PlayerClick");
-                                    //endregion
-                                    root_rendering_component.card_on_click(game_data);
+                                if game_data.is_server_authoritative {
+                                    //the server validates the click and sends its state
+                                    let ws_message = WsMessage::ServerClick {
+                                        ws_client_instance: game_data.my_ws_client_instance,
+                                        card_index: this_click_card_index,
+                                    };
+                                    send_to_server(&mut game_data, &ws_message);
+                                    return;
+                                }
+                                //the rules engine knows if the click is usefull
+                                let vec_events = game_data.game_engine.apply(GameAction::Click {
+                                    card_index: this_click_card_index,
+                                });
+                                //the local game has nobody to send the click to
+                                let is_local_game = game_data.is_local_game();
+                                if is_local_game {
+                                    local_game_after_click(&mut game_data, &vec_events);
+                                }
+                                for game_event in vec_events.iter().filter(|_| !is_local_game) {
+                                    if let GameEvent::CardFlipped {
+                                        card_index,
+                                        count_click_inside_one_turn,
+                                    } = game_event
+                                    {
+                                        //region: send WsMessage over websocket
+                                        let ws_client_instance = game_data.my_ws_client_instance;
+                                        //only the deck owner can reveal the card
+                                        let card_reveal = game_data.game_engine.reveal(*card_index);
+                                        let state_hash = game_data.game_engine.state_hash();
+                                        send_game_message(&mut game_data, |seq| {
+                                            WsMessage::PlayerClick {
+                                                ws_client_instance,
+                                                card_index: *card_index,
+                                                count_click_inside_one_turn:
+                                                    *count_click_inside_one_turn,
+                                                card_reveal,
+                                                state_hash,
+                                                seq,
+                                            }
+                                        });
+                                        //endregion
+                                    }
                                 }
+                                root_rendering_component
+                                    .react_to_game_events(&mut game_data, &vec_events);
+                                save_game_to_session_storage(&mut game_data);
                                 // Finally, re-render the component on the next animation frame.
                                 vdom.schedule_render();
                             }
@@ -687,31 +1391,31 @@ impl Render for RootRenderingComponent {
             let game_data = root_rendering_component.rc.borrow();
             //if the Spellings are visible, than don't show GameTitle, because there is not
             //enought space on smartphones
-            if game_data.card_index_of_first_click != 0 || game_data.card_index_of_second_click != 0
+            if game_data.game_engine.card_index_of_first_click != 0
+                || game_data.game_engine.card_index_of_second_click != 0
             {
                 //if the two opened card match use green else use red color
                 let color; //haha variable does not need to be mutable. Great !
 
-                if game_data
-                    .vec_cards
-                    .get(game_data.card_index_of_first_click)
-                    .expect("error index")
-                    .card_number_and_img_src
-                    == game_data
-                        .vec_cards
-                        .get(game_data.card_index_of_second_click)
-                        .expect("error index")
-                        .card_number_and_img_src
-                {
+                if game_data.game_engine.is_pair_matched() {
                     color = "green";
-                } else if game_data.card_index_of_first_click == 0
-                    || game_data.card_index_of_second_click == 0
+                } else if game_data.game_engine.card_index_of_first_click == 0
+                    || game_data.game_engine.card_index_of_second_click == 0
+                    || !game_data.game_engine.is_pair_revealed()
                 {
                     color = "yellow";
                 } else {
                     color = "red";
                 }
 
+                //the labels are shown only when the cards file is loaded
+                let card_label = |card_index| {
+                    game_data
+                        .card_metadata(card_index)
+                        .map_or(String::new(), |card_metadata| card_metadata.label.clone())
+                };
+                let first_card_name = card_label(game_data.game_engine.card_index_of_first_click);
+                let second_card_name = card_label(game_data.game_engine.card_index_of_second_click);
                 {
                     //return
                     div(bump)
@@ -726,10 +1430,7 @@ impl Render for RootRenderingComponent {
                         .attr("class", "grid_item")
                         .attr("style", "text-align: left;")
                         .children([text(
-bumpalo::format!(in bump, "{}",
- root_rendering_component.get_spelling().name.get(game_data.vec_cards.get(game_data.card_index_of_first_click).expect("error index")
-                                .card_number_and_img_src).expect("error index")
-)
+bumpalo::format!(in bump, "{}", first_card_name)
                         .into_bump_str(),
                         )])
                         .finish(),
@@ -737,10 +1438,7 @@ bumpalo::format!(in bump, "{}",
                         .attr("class", "grid_item")
                         .attr("style", "text-align: right;")
                         .children([text(
-                            bumpalo::format!(in bump, "{}",
-                            root_rendering_component.get_spelling().name.get(game_data.vec_cards.get(game_data.card_index_of_second_click).expect("error index")
-                                .card_number_and_img_src).expect("error index")
-                                )
+                            bumpalo::format!(in bump, "{}", second_card_name)
                         .into_bump_str(),
                         )])
                         .finish(),
@@ -775,135 +1473,965 @@ bumpalo::format!(in bump, "{}",
             if let GameState::Start = game_data.game_state {
                 // 1S Ask Player2 to play!
                 console::log_1(&"GameState::Start".into());
-                //return Ask Player2 to play!
-                h3(bump)
-                    .attr("id", "ws_elem")
-                    .attr("style", "color:green;")
-                    .children([text(
-                        //show Ask Player2 to Play!
-                        bumpalo::format!(in bump, "Ask other Player to play! {}", "")
-                            .into_bump_str(),
-                    )])
-                    .on("click", move |root, vdom, _event| {
-                        let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
-                        //this game_data mutable reference is dropped on the end of the function
-                        let mut game_data = root_rendering_component.rc.borrow_mut();
-                        //region: send WsMessage over websocket
-                        game_data.this_machine_player_number = 1;
-                        game_data.game_state = GameState::Asking;
-                        game_data
-                            .ws
-                            .send_with_str(
-                                &serde_json::to_string(&WsMessage::WantToPlay {
-                                    ws_client_instance: game_data.my_ws_client_instance,
-                                })
-                                .expect("error sending test"),
-                            )
-                            .expect("Failed to send");
-                        //endregion
-                        vdom.schedule_render();
-                    })
+                if game_data.pack_cards.is_none() {
+                    //the game starts after the content is loaded
+                    return h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:orange;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Loading the content...{}", "")
+                                .into_bump_str(),
+                        )])
+                        .finish();
+                }
+                if !game_data.room_code.is_empty() {
+                    //the link with the room code was opened before the room was announced
+                    return h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:red;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Wait for the other player in the room {}.", game_data.room_code)
+                                .into_bump_str(),
+                        )])
+                        .finish();
+                }
+                //return choose the grid size, create a room or join a room from the lobby
+                div(bump)
+                    .children([
+                        div_player_name_input(root_rendering_component, bump),
+                        div_grid_size_picker(root_rendering_component, bump),
+                        div_referee_picker(root_rendering_component, bump),
+                        div_content_pack_picker(root_rendering_component, bump),
+                        h3(bump)
+                            .attr("id", "ws_elem")
+                            .attr("style", "color:green;")
+                            .children([text(
+                                //show Ask Player2 to Play!
+                                bumpalo::format!(in bump, "Create a room and ask other Player to play! {}", "")
+                                    .into_bump_str(),
+                            )])
+                            .on("click", move |root, vdom, _event| {
+                                let root_rendering_component =
+                                    root.unwrap_mut::<RootRenderingComponent>();
+                                //this game_data mutable reference is dropped on the end of the function
+                                let mut game_data = root_rendering_component.rc.borrow_mut();
+                                //region: send WsMessage over websocket
+                                game_data.this_machine_player_number = 1;
+                                game_data.game_state = GameState::Asking;
+                                game_data.room_code = generate_room_code();
+                                send_want_to_play(&mut game_data);
+                                //endregion
+                                vdom.schedule_render();
+                            })
+                            .finish(),
+                        h3(bump)
+                            .attr("style", "color:green;")
+                            .children([text(
+                                bumpalo::format!(in bump, "Play alone! {}", "")
+                                    .into_bump_str(),
+                            )])
+                            .on("click", move |root, vdom, _event| {
+                                let root_rendering_component =
+                                    root.unwrap_mut::<RootRenderingComponent>();
+                                //this game_data mutable reference is dropped on the end of the function
+                                let mut game_data = root_rendering_component.rc.borrow_mut();
+                                if let Err(error) = start_solo(&mut game_data) {
+                                    game_data.show_error(error);
+                                }
+                                vdom.schedule_render();
+                            })
+                            .finish(),
+                        h3(bump)
+                            .attr("style", "color:green;")
+                            .children([text(
+                                bumpalo::format!(in bump, "Play against the computer! {}", "")
+                                    .into_bump_str(),
+                            )])
+                            .on("click", move |root, vdom, _event| {
+                                let root_rendering_component =
+                                    root.unwrap_mut::<RootRenderingComponent>();
+                                //this game_data mutable reference is dropped on the end of the function
+                                let mut game_data = root_rendering_component.rc.borrow_mut();
+                                if let Err(error) = start_vs_computer(&mut game_data) {
+                                    game_data.show_error(error);
+                                }
+                                vdom.schedule_render();
+                            })
+                            .finish(),
+                        div_computer_memory_picker(root_rendering_component, bump),
+                        h3(bump)
+                            .attr("style", "color:green;")
+                            .children([text(
+                                bumpalo::format!(in bump, "Play on this device! {}", "")
+                                    .into_bump_str(),
+                            )])
+                            .on("click", move |root, vdom, _event| {
+                                let root_rendering_component =
+                                    root.unwrap_mut::<RootRenderingComponent>();
+                                //this game_data mutable reference is dropped on the end of the function
+                                let mut game_data = root_rendering_component.rc.borrow_mut();
+                                if let Err(error) = start_hot_seat(&mut game_data) {
+                                    game_data.show_error(error);
+                                }
+                                vdom.schedule_render();
+                            })
+                            .finish(),
+                        div_hot_seat_player_count_picker(root_rendering_component, bump),
+                        div_lobby(root_rendering_component, bump),
+                    ])
                     .finish()
             } else if let GameState::Asking = game_data.game_state {
-                //return wait for the other player
-                div_wait_for_other_player(bump)
-            } else if let GameState::Asked = game_data.game_state {
-                // 2S Click here to Accept play!
+                //return the room code and link to share and wait for the other player
+                let room_text = h4(bump)
+                    .children([text(
+                        bumpalo::format!(in bump, "Room {}. Share the link: {}",
+                            game_data.room_code,
+                            room_link(&game_data.room_code)
+                                .unwrap_or_else(|error| error.to_string())
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish();
+                if !game_data.is_server_authoritative {
+                    return div(bump)
+                        .children([room_text, div_wait_for_other_player(bump)])
+                        .finish();
+                }
+                //with the server as referee the host starts when enough players joined
+                let player_count = game_data.vec_player_names.len();
+                let start_or_wait = if player_count >= 2 {
+                    h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:green;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Start the game with {} players!", player_count)
+                                .into_bump_str(),
+                        )])
+                        .on("click", move |root, vdom, _event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            start_game(&mut game_data);
+                            vdom.schedule_render();
+                        })
+                        .finish()
+                } else {
+                    div_wait_for_other_player(bump)
+                };
+                div(bump)
+                    .children([
+                        room_text,
+                        div_room_players(root_rendering_component, bump),
+                        start_or_wait,
+                    ])
+                    .finish()
+            } else if let GameState::Joined = game_data.game_state {
+                //return the players in the room and wait for the host
+                div(bump)
+                    .children([
+                        div_room_players(root_rendering_component, bump),
+                        h3(bump)
+                            .attr("id", "ws_elem")
+                            .attr("style", "color:red;")
+                            .children([text(
+                                bumpalo::format!(in bump, "Joined the room {}. Wait for {} to start the game.",
+                                    game_data.room_code,
+                                    game_data.player_name(1)
+                                )
+                                .into_bump_str(),
+                            )])
+                            .finish(),
+                    ])
+                    .finish()
+            } else if let GameState::Asked = game_data.game_state {
+                // 2S Click here to Accept play!
                 console::log_1(&"GameState::Asked".into());
+                if game_data.pack_cards.is_none() {
+                    //the deck is built after the content is loaded
+                    return h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:orange;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Loading the content...{}", "")
+                                .into_bump_str(),
+                        )])
+                        .finish();
+                }
                 //return Click here to Accept play
                 h3(bump)
-                    .attr("id", "ws_elem")
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:green;")
+                    .children([text(
+                        //show Ask Player2 to Play!
+                        bumpalo::format!(in bump, "Click here to Accept play! {}", "")
+                            .into_bump_str(),
+                    )])
+                    .on("click", move |root, vdom, _event| {
+                        let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                        //this game_data mutable reference is dropped on the end of the function
+                        let mut game_data = root_rendering_component.rc.borrow_mut();
+                        //region: send WsMessage over websocket
+                        if game_data.is_server_authoritative {
+                            join_room(&mut game_data);
+                        } else if let Err(error) = accept_play(&mut game_data) {
+                            game_data.show_error(error);
+                        }
+                        //endregion
+                        vdom.schedule_render();
+                    })
+                    .finish()
+            } else if let GameState::GameOver = game_data.game_state {
+                //return the results and the rematch
+                div_game_over(root_rendering_component, bump)
+            } else if game_data.is_hot_seat {
+                //return the hand-over screen, the take turn or play
+                div_hot_seat_status(root_rendering_component, bump)
+            } else if game_data.is_solo {
+                //return the clock of the solo game
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:orange;")
+                    .children([text(
+                        bumpalo::format!(in bump, "Play alone! {}",
+                            elapsed_text(js_sys::Date::now() - game_data.game_start_ms)
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish()
+            } else if game_data.computer_player.is_some()
+                && ((game_data.game_engine.player_turn == 2
+                    && game_data.game_engine.count_click_inside_one_turn < 2)
+                    || (game_data.game_engine.player_turn == 1
+                        && game_data.game_engine.count_click_inside_one_turn >= 2))
+            {
+                //return the computer is on the move
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:red;")
+                    .children([text(
+                        bumpalo::format!(in bump, "{}",
+                            if game_data.game_engine.player_turn == 2 {
+                                "The computer plays."
+                            } else {
+                                "The computer takes the turn."
+                            }
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish()
+            } else if game_data.game_engine.count_click_inside_one_turn >= 2 {
+                //only the next player takes the turn
+                if game_data.this_machine_player_number != game_data.game_engine.next_player()
+                    || !game_data.game_engine.is_pair_revealed()
+                {
+                    //return wait for the other player
+                    div_wait_for_other_player(bump)
+                } else {
+                    //return Click here to take your turn
+                    h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:green;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Click here to take your turn !{}", "")
+                                .into_bump_str(),
+                        )])
+                        .on("click", move |root, vdom, _event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if game_data.is_server_authoritative {
+                                //the server validates the take turn and sends its state
+                                let ws_message = WsMessage::ServerTakeTurn {
+                                    ws_client_instance: game_data.my_ws_client_instance,
+                                };
+                                send_to_server(&mut game_data, &ws_message);
+                                return;
+                            }
+                            let vec_events = game_data.game_engine.apply(GameAction::TakeTurn);
+                            if !vec_events.is_empty() {
+                                //region: send WsMessage over websocket
+                                let ws_client_instance = game_data.my_ws_client_instance;
+                                let state_hash = game_data.game_engine.state_hash();
+                                send_game_message(&mut game_data, |seq| WsMessage::PlayerChange {
+                                    ws_client_instance,
+                                    state_hash,
+                                    seq,
+                                });
+                                //endregion
+                                save_game_to_session_storage(&mut game_data);
+                            }
+                            // Finally, re-render the component on the next animation frame.
+                            vdom.schedule_render();
+                        })
+                        .finish()
+                }
+            } else if game_data.game_engine.count_click_inside_one_turn < 2 {
+                if game_data.this_machine_player_number == game_data.game_engine.player_turn {
+                    h3(bump)
+                        .attr("id", "ws_elem")
+                        .attr("style", "color:orange;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Play !{}", "").into_bump_str(),
+                        )])
+                        .finish()
+                } else {
+                    //return wait for the other player
+                    div_wait_for_other_player(bump)
+                }
+            } else {
+                //unpredictable situation
+                //return
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .children([text(
+                        bumpalo::format!(in bump, "gamestate: {} player {}", game_data.game_state.as_ref(),game_data.this_machine_player_number)
+                            .into_bump_str(),
+                    )])
+                    .finish()
+            }
+        }
+        ///the open rooms of the other players. A click joins the room and accepts to play.
+        fn div_lobby<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let mut vec_room_bump = Vec::new();
+            vec_room_bump.push(
+                h4(bump)
+                    .children([text(
+                        bumpalo::format!(in bump, "Open rooms: {}", game_data.vec_open_rooms.len())
+                            .into_bump_str(),
+                    )])
+                    .finish(),
+            );
+            for open_room in &game_data.vec_open_rooms {
+                let room_code = open_room.room_code.clone();
+                let ws_client_instance = open_room.ws_client_instance;
+                let grid_size = open_room.grid_size;
+                let is_server_authoritative = open_room.is_server_authoritative;
+                let content_folder_name = open_room.content_folder_name.clone();
+                let player_name = open_room.player_name.clone();
+                //the name of the pack from my manifest
+                let content_pack_name = game_data
+                    .packs_manifest
+                    .find(&content_folder_name)
+                    .map_or(content_folder_name.as_str(), |content_pack| {
+                        content_pack.name.as_str()
+                    });
+                vec_room_bump.push(
+                    h4(bump)
+                        .attr("style", "color:green;text-decoration:underline;")
+                        .children([text(
+                            bumpalo::format!(in bump, "Join the room {} of {} ({}x{}, {}{})",
+                                room_code,
+                                if open_room.player_name.is_empty() {"player1"} else {open_room.player_name.as_str()},
+                                grid_size.rows, grid_size.cols, content_pack_name,
+                                if is_server_authoritative {", server referee, up to 6 players"} else {""}
+                            )
+                            .into_bump_str(),
+                        )])
+                        .on("click", move |root, vdom, _event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            game_data.room_code = room_code.clone();
+                            game_data.other_ws_client_instance = ws_client_instance;
+                            game_data.is_server_authoritative = is_server_authoritative;
+                            game_data.vec_player_names = vec![player_name.clone()];
+                            //the content pack and the cards for the grid size of the room
+                            if let Err(error) = join_content_pack(
+                                &mut game_data,
+                                &vdom,
+                                &content_folder_name,
+                                grid_size,
+                            ) {
+                                game_data.show_error(error);
+                                vdom.schedule_render();
+                                return;
+                            }
+                            //the server deals the cards. The deck of the players needs the cards of the pack.
+                            if is_server_authoritative {
+                                join_room(&mut game_data);
+                            } else if game_data.pack_cards.is_some() {
+                                if let Err(error) = accept_play(&mut game_data) {
+                                    game_data.show_error(error);
+                                }
+                            } else {
+                                game_data.game_state = GameState::Asked;
+                            }
+                            vdom.schedule_render();
+                        })
+                        .finish(),
+                );
+            }
+            div(bump).children(vec_room_bump).finish()
+        }
+        ///the name of the player is shown to the other players
+        fn div_player_name_input<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            h4(bump)
+                .children([
+                    text(bumpalo::format!(in bump, "Your name: {}", "").into_bump_str()),
+                    input(bump)
+                        .attr("type", "text")
+                        .attr("maxlength", "20")
+                        .attr(
+                            "value",
+                            bumpalo::format!(in bump, "{}", game_data.my_player_name)
+                                .into_bump_str(),
+                        )
+                        .on("input", move |root, _vdom, event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if let Some(input_element) = event.target().and_then(|target| {
+                                target.dyn_into::<web_sys::HtmlInputElement>().ok()
+                            }) {
+                                game_data.my_player_name = input_element.value().trim().to_string();
+                            }
+                        })
+                        .finish(),
+                ])
+                .finish()
+        }
+        ///the players that joined the room of the server referee, from the host to the last
+        fn div_room_players<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let mut vec_player_bump = Vec::new();
+            vec_player_bump.push(
+                h4(bump)
+                    .children([text(
+                        bumpalo::format!(in bump, "Players: {} of {}",
+                            game_data.vec_player_names.len(), MAX_PLAYERS
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish(),
+            );
+            for player in 1..=game_data.vec_player_names.len() {
+                vec_player_bump.push(
+                    h5(bump)
+                        .children([text(
+                            bumpalo::format!(in bump, "{}", game_data.player_name(player))
+                                .into_bump_str(),
+                        )])
+                        .finish(),
+                );
+            }
+            div(bump).children(vec_player_bump).finish()
+        }
+        ///the first player chooses the grid size before asking the other player to play
+        fn div_grid_size_picker<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let mut vec_grid_size_bump = Vec::new();
+            for grid_size in GRID_SIZES
+                .iter()
+                .filter(|grid_size| grid_size.is_possible(game_data.content_pack.card_count))
+            {
+                //the grid_size is Copy and it is moved into the closure
+                let grid_size = *grid_size;
+                vec_grid_size_bump.push(
+                    div(bump)
+                        .attr("class", "grid_item")
+                        .attr(
+                            "style",
+                            bumpalo::format!(in bump, "text-decoration:{}",
+                                if game_data.game_engine.grid_size == grid_size {"underline"} else {"none"}
+                            )
+                            .into_bump_str(),
+                        )
+                        .children([text(
+                            bumpalo::format!(in bump, "{}x{}", grid_size.rows, grid_size.cols)
+                                .into_bump_str(),
+                        )])
+                        .on("click", move |root, vdom, _event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            //a new deck face down shows the chosen grid size
+                            match game_data.new_deck(grid_size) {
+                                Ok(game_engine) => game_data.game_engine = game_engine,
+                                Err(error) => game_data.show_error(error),
+                            }
+                            vdom.schedule_render();
+                        })
+                        .finish(),
+                );
+            }
+            div(bump)
+                .attr("class", "grid_container_players")
+                .attr(
+                    "style",
+                    bumpalo::format!(in bump, "grid-template-columns: repeat({}, auto);",
+                        vec_grid_size_bump.len()
+                    )
+                    .into_bump_str(),
+                )
+                .children(vec_grid_size_bump)
+                .finish()
+        }
+        ///the first player chooses the content pack. The other player uses the same.
+        fn div_content_pack_picker<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let mut vec_content_pack_bump = Vec::new();
+            for content_pack in &game_data.packs_manifest.packs {
+                let content_folder_name = content_pack.folder.clone();
+                vec_content_pack_bump.push(
+                    div(bump)
+                        .attr("class", "grid_item")
+                        .attr(
+                            "style",
+                            bumpalo::format!(in bump, "text-decoration:{}",
+                                if game_data.content_pack.folder == content_pack.folder {"underline"} else {"none"}
+                            )
+                            .into_bump_str(),
+                        )
+                        .children([text(
+                            bumpalo::format!(in bump, "{}", content_pack.name).into_bump_str(),
+                        )])
+                        .on("click", move |root, vdom, _event| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if let Err(error) =
+                                choose_content_pack(&mut game_data, &vdom, &content_folder_name)
+                            {
+                                game_data.show_error(error);
+                            }
+                            //the new deck needs enough images for the grid size
+                            let card_count = game_data.content_pack.card_count;
+                            let mut grid_size = game_data.game_engine.grid_size;
+                            if !grid_size.is_possible(card_count) {
+                                grid_size = GridSize::default();
+                            }
+                            match game_data.new_deck(grid_size) {
+                                Ok(game_engine) => game_data.game_engine = game_engine,
+                                Err(error) => game_data.show_error(error),
+                            }
+                            vdom.schedule_render();
+                        })
+                        .finish(),
+                );
+            }
+            div(bump)
+                .attr("class", "grid_container_players")
+                .attr(
+                    "style",
+                    bumpalo::format!(in bump, "grid-template-columns: repeat({}, auto);",
+                        vec_content_pack_bump.len().max(1)
+                    )
+                    .into_bump_str(),
+                )
+                .children(vec_content_pack_bump)
+                .finish()
+        }
+        ///the first player chooses who is the referee: the players or the server
+        fn div_referee_picker<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            h4(bump)
+                .attr("style", "text-decoration:underline;")
+                .children([text(
+                    bumpalo::format!(in bump, "Referee: {}",
+                        if game_data.is_server_authoritative {"the server"} else {"the players"}
+                    )
+                    .into_bump_str(),
+                )])
+                .on("click", move |root, vdom, _event| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    game_data.is_server_authoritative = !game_data.is_server_authoritative;
+                    vdom.schedule_render();
+                })
+                .finish()
+        }
+        ///the memory of the computer opponent: easy, medium or perfect
+        fn div_computer_memory_picker<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            h4(bump)
+                .attr("style", "text-decoration:underline;")
+                .children([text(
+                    bumpalo::format!(in bump, "Computer memory: {}",
+                        game_data.computer_memory_strength.name()
+                    )
+                    .into_bump_str(),
+                )])
+                .on("click", move |root, vdom, _event| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    game_data.computer_memory_strength = game_data.computer_memory_strength.next();
+                    vdom.schedule_render();
+                })
+                .finish()
+        }
+        ///the count of players that share this device: from 2 to 6
+        fn div_hot_seat_player_count_picker<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            h4(bump)
+                .attr("style", "text-decoration:underline;")
+                .children([text(
+                    bumpalo::format!(in bump, "Players on this device: {}",
+                        game_data.hot_seat_player_count
+                    )
+                    .into_bump_str(),
+                )])
+                .on("click", move |root, vdom, _event| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    game_data.hot_seat_player_count =
+                        if game_data.hot_seat_player_count >= MAX_PLAYERS {
+                            2
+                        } else {
+                            game_data.hot_seat_player_count + 1
+                        };
+                    vdom.schedule_render();
+                })
+                .finish()
+        }
+        ///the players of the hot seat pass the device after 2 cards that did not match.
+        ///The hand-over screen tells who takes the device.
+        fn div_hot_seat_status<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let game_engine = &game_data.game_engine;
+            if game_data.is_hand_over {
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:green;")
+                    .children([text(
+                        bumpalo::format!(in bump, "{}, your turn! Click here when you have the device.",
+                            game_data.player_name(game_engine.player_turn)
+                        )
+                        .into_bump_str(),
+                    )])
+                    .on("click", move |root, vdom, _event| {
+                        let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                        //this game_data mutable reference is dropped on the end of the function
+                        let mut game_data = root_rendering_component.rc.borrow_mut();
+                        game_data.is_hand_over = false;
+                        save_game_to_session_storage(&mut game_data);
+                        vdom.schedule_render();
+                    })
+                    .finish()
+            } else if game_engine.count_click_inside_one_turn >= 2
+                && game_engine.is_pair_revealed()
+                && !game_engine.is_pair_matched()
+            {
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:green;")
+                    .children([text(
+                        bumpalo::format!(in bump, "No match. Click here and pass the device to {}.",
+                            game_data.player_name(game_engine.next_player())
+                        )
+                        .into_bump_str(),
+                    )])
+                    .on("click", move |root, vdom, _event| {
+                        let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                        //this game_data mutable reference is dropped on the end of the function
+                        let mut game_data = root_rendering_component.rc.borrow_mut();
+                        hot_seat_take_turn(&mut game_data);
+                        vdom.schedule_render();
+                    })
+                    .finish()
+            } else {
+                h3(bump)
+                    .attr("id", "ws_elem")
+                    .attr("style", "color:orange;")
+                    .children([text(
+                        bumpalo::format!(in bump, "{}, play!",
+                            game_data.player_name(game_engine.player_turn)
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish()
+            }
+        }
+        ///the winner or the draw, the points, the clicks of all players and the duration, then the rematch
+        fn div_game_over<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let game_engine = &game_data.game_engine;
+            let result_text = match game_engine.game_result() {
+                _ if game_data.is_solo => "All pairs found!".to_string(),
+                Some(GameResult::Winner { player, points }) => format!(
+                    "{} wins with {} points!",
+                    game_data.player_name(player),
+                    points
+                ),
+                Some(GameResult::Draw {
+                    vec_players,
+                    points,
+                }) => format!(
+                    "Draw between {} with {} points!",
+                    vec_players
+                        .iter()
+                        .map(|player| game_data.player_name(*player))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    points
+                ),
+                None => String::new(),
+            };
+            let points_text = (1..=game_engine.player_count)
+                .map(|player| {
+                    format!(
+                        "{}: {}",
+                        game_data.player_name(player),
+                        game_engine.points(player)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            //the engine counts the clicks of all the players together
+            let clicks_label = if game_data.is_solo {
+                "Clicks"
+            } else {
+                "Clicks of all players"
+            };
+            let rematch_count = game_data.vec_rematch_requests.len();
+            let rematch_text = if game_data
+                .vec_rematch_requests
+                .contains(&game_data.my_ws_client_instance)
+            {
+                format!(
+                    "Wait for the rematch: {} of {} players want it.",
+                    rematch_count, game_engine.player_count
+                )
+            } else if rematch_count > 0 {
+                format!(
+                    "Rematch! {} of {} players want it.",
+                    rematch_count, game_engine.player_count
+                )
+            } else {
+                "Rematch!".to_string()
+            };
+            let mut vec_results = vec![h3(bump)
+                .attr("id", "ws_elem")
+                .attr("style", "color:green;")
+                .children([text(
+                    bumpalo::format!(in bump, "{}", result_text).into_bump_str(),
+                )])
+                .finish()];
+            if !game_data.is_solo {
+                vec_results.push(
+                    h4(bump)
+                        .children([text(
+                            bumpalo::format!(in bump, "{}", points_text).into_bump_str(),
+                        )])
+                        .finish(),
+                );
+            }
+            vec_results.push(
+                h4(bump)
+                    .children([text(
+                        bumpalo::format!(in bump, "{}: {}. Duration: {}.",
+                            clicks_label,
+                            game_engine.count_all_clicks,
+                            elapsed_text(game_data.game_end_ms - game_data.game_start_ms)
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish(),
+            );
+            vec_results.push(
+                h3(bump)
                     .attr("style", "color:green;")
                     .children([text(
-                        //show Ask Player2 to Play!
-                        bumpalo::format!(in bump, "Click here to Accept play! {}", "")
-                            .into_bump_str(),
+                        bumpalo::format!(in bump, "{}", rematch_text).into_bump_str(),
                     )])
                     .on("click", move |root, vdom, _event| {
                         let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
                         //this game_data mutable reference is dropped on the end of the function
                         let mut game_data = root_rendering_component.rc.borrow_mut();
-                        //region: send WsMessage over websocket
-                        game_data.this_machine_player_number = 2;
-                        game_data.player_turn = 1;
-                        game_data.game_state = GameState::Play;
-
-                        game_data
-                            .ws
-                            .send_with_str(
-                                &serde_json::to_string(&WsMessage::AcceptPlay {
-                                    ws_client_instance: game_data.my_ws_client_instance,
-                                    //send the vector of cards because both players need cards in the same location.
-                                    card_grid_data: serde_json::to_string(&game_data.vec_cards)
-                                        .expect("error serde_json"),
-                                })
-                                .expect("error sending test"),
-                            )
-                            .expect("Failed to send");
-                        //endregion
+                        if let Err(error) = request_rematch(&mut game_data) {
+                            game_data.show_error(error);
+                        }
                         vdom.schedule_render();
                     })
+                    .finish(),
+            );
+            div(bump).children(vec_results).finish()
+        }
+        ///the connection indicator: green when connected, red while reconnecting
+        fn div_connection_status<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let (color, connection_text) = match game_data.connection_status {
+                ConnectionStatus::Connecting => (
+                    "orange",
+                    bumpalo::format!(in bump, "Connecting to the server.{}", ""),
+                ),
+                ConnectionStatus::Connected => {
+                    ("green", bumpalo::format!(in bump, "Connected.{}", ""))
+                }
+                ConnectionStatus::Reconnecting { attempt } => (
+                    "red",
+                    bumpalo::format!(in bump, "Connection lost. Reconnecting, attempt {}.", attempt),
+                ),
+            };
+            h5(bump)
+                .attr(
+                    "style",
+                    bumpalo::format!(in bump, "color:{};", color).into_bump_str(),
+                )
+                .children([text(connection_text.into_bump_str())])
+                .finish()
+        }
+        ///the game in progress can be discarded only explicitly, because a refresh restores it
+        fn div_new_game<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            if let GameState::Play | GameState::GameOver = game_data.game_state {
+                h5(bump)
+                    .attr("style", "text-decoration:underline;")
+                    .children([text(
+                        bumpalo::format!(in bump, "New game{}", "").into_bump_str(),
+                    )])
+                    .on("click", move |root, vdom, _event| {
+                        let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                        //this game_data mutable reference is dropped on the end of the function
+                        let mut game_data = root_rendering_component.rc.borrow_mut();
+                        //without removing the saved game the reload would restore it
+                        if let Err(error) = remove_game_from_session_storage() {
+                            game_data.show_error(error);
+                            vdom.schedule_render();
+                            return;
+                        }
+                        if let Err(error) = browser_window().and_then(|window| {
+                            window
+                                .location()
+                                .reload()
+                                .map_err(|err| Mem2Error::Browser(js_error_text(&err)))
+                        }) {
+                            game_data.show_error(error);
+                            vdom.schedule_render();
+                        }
+                    })
                     .finish()
-            } else if game_data.count_click_inside_one_turn >= 2 {
-                if game_data.this_machine_player_number == game_data.player_turn {
-                    //return wait for the other player
-                    div_wait_for_other_player(bump)
-                } else {
-                    //return Click here to take your turn
-                    h3(bump)
-                        .attr("id", "ws_elem")
-                        .attr("style", "color:green;")
+            } else {
+                div(bump).finish()
+            }
+        }
+        ///the errors are shown until the player dismisses them with a click
+        fn div_error_panel<'a, 'bump>(
+            root_rendering_component: &'a RootRenderingComponent,
+            bump: &'bump Bump,
+        ) -> Node<'bump>
+        where
+            'a: 'bump,
+        {
+            //this game_data mutable reference is dropped on the end of the function
+            let game_data = root_rendering_component.rc.borrow();
+            let mut vec_error_bump = Vec::new();
+            for (error_index, error) in game_data.vec_errors.iter().enumerate() {
+                vec_error_bump.push(
+                    h4(bump)
+                        .attr("style", "color:red;")
                         .children([text(
-                            bumpalo::format!(in bump, "Click here to take your turn !{}", "")
+                            bumpalo::format!(in bump, "{} (click to dismiss)", error)
                                 .into_bump_str(),
                         )])
                         .on("click", move |root, vdom, _event| {
                             let root_rendering_component =
                                 root.unwrap_mut::<RootRenderingComponent>();
                             //this game_data mutable reference is dropped on the end of the function
-                            //clippy is wrong about dropping the mut. I need it.
-                            let game_data = root_rendering_component.rc.borrow_mut();
-                            //region: send WsMessage over websocket
-                            game_data
-                                .ws
-                                .send_with_str(
-                                    &serde_json::to_string(&WsMessage::PlayerChange {
-                                        ws_client_instance: game_data.my_ws_client_instance,
-                                    })
-                                    .expect("error sending PlayerChange"),
-                                )
-                                .expect("Failed to send PlayerChange");
-                            //endregion
-                            root_rendering_component.take_turn(game_data);
-                            // Finally, re-render the component on the next animation frame.
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if error_index < game_data.vec_errors.len() {
+                                game_data.vec_errors.remove(error_index);
+                            }
                             vdom.schedule_render();
                         })
-                        .finish()
-                }
-            } else if game_data.count_click_inside_one_turn < 2 {
-                if game_data.this_machine_player_number == game_data.player_turn {
-                    h3(bump)
-                        .attr("id", "ws_elem")
-                        .attr("style", "color:orange;")
-                        .children([text(
-                            bumpalo::format!(in bump, "Play !{}", "").into_bump_str(),
-                        )])
-                        .finish()
-                } else {
-                    //return wait for the other player
-                    div_wait_for_other_player(bump)
-                }
-            } else {
-                //unpredictable situation
-                //return
-                h3(bump)
-                    .attr("id", "ws_elem")
-                    .children([text(
-                        bumpalo::format!(in bump, "gamestate: {} player {}", game_data.game_state.as_ref(),game_data.this_machine_player_number)
-                            .into_bump_str(),
-                    )])
-                    .finish()
+                        .finish(),
+                );
             }
+            div(bump).children(vec_error_bump).finish()
         }
         ///the text 'wait for other player' is used multiple times
         fn div_wait_for_other_player(bump: &Bump) -> Node {
@@ -928,14 +2456,24 @@ bumpalo::format!(in bump, "{}",
                 //div for the css grid object defined in css with <img> inside
                 div(bump)
                     .attr("class", "grid_container")
-                    .attr("style", "margin-left: auto;margin-right: auto;")
+                    .attr(
+                        "style",
+                        bumpalo::format!(in bump,
+                            "margin-left: auto;margin-right: auto;grid-template-columns: repeat({}, auto);",
+                            game_data.game_engine.grid_size.cols
+                        )
+                        .into_bump_str(),
+                    )
                     .children(div_grid_items(self, bump))
                     .finish(),
                 self.players_and_scores.render(bump),
                 div_game_status_and_player_actions(self, bump),
+                div_connection_status(self, bump),
+                div_new_game(self, bump),
+                div_error_panel(self, bump),
                 h5(bump)
                     .children([text(
-                        bumpalo::format!(in bump, "Count of Clicks: {}", game_data.count_all_clicks)
+                        bumpalo::format!(in bump, "Count of Clicks: {}", game_data.game_engine.count_all_clicks)
                             .into_bump_str(),
                     )])
                     .finish(),
@@ -981,107 +2519,625 @@ impl Render for RulesAndDescription {
     }
 }
 
-impl Render for PlayersAndScores {
-    ///This rendering will be rendered and then cached . It will not be rerendered untill invalidation.
-    ///It is ivalidate, when the points change.
-    ///html element to with scores for 2 players
-    fn render<'a, 'bump>(&'a self, bump: &'bump Bump) -> Node<'bump>
-    where
-        'a: 'bump,
+impl Render for PlayersAndScores {
+    ///This rendering will be rendered and then cached . It will not be rerendered untill invalidation.
+    ///It is ivalidate, when the points change.
+    ///html element with the name and the score of every player
+    fn render<'a, 'bump>(&'a self, bump: &'bump Bump) -> Node<'bump>
+    where
+        'a: 'bump,
+    {
+        //this game_data mutable reference is dropped on the end of the function
+        let game_data = self.rc.borrow();
+        let player_count = game_data.game_engine.player_count;
+        let mut vec_player_bump = Vec::new();
+        for player in 1..=player_count {
+            vec_player_bump.push(
+                div(bump)
+                    .attr("class", "grid_item")
+                    .attr(
+                        "style",
+                        bumpalo::format!(in bump,"text-align: center;color:{};text-decoration:{}",
+                            if game_data.game_engine.player_turn==player {"green"} else {"red"},
+                            if game_data.this_machine_player_number==player {"underline"} else {"none"}
+                        )
+                        .into_bump_str(),
+                    )
+                    .children([text(
+                        bumpalo::format!(in bump, "{}: {}",
+                            game_data.player_name(player),
+                            game_data.game_engine.points(player)
+                        )
+                        .into_bump_str(),
+                    )])
+                    .finish(),
+            );
+        }
+        //return
+        div(bump)
+            .attr("class", "grid_container_players")
+            .attr(
+                "style",
+                bumpalo::format!(in bump, "grid-template-columns: repeat({}, auto);", player_count)
+                    .into_bump_str(),
+            )
+            .children(vec_player_bump)
+            .finish()
+    }
+}
+//endregion
+
+//region: websocket communication
+///setup websocket connection for the first connection and for every reconnect
+fn setup_ws_connection() -> Mem2Result<WebSocket> {
+    //web-sys has websocket for Rust exactly like javascript has¸
+    let ws_url = ws_url(&browser_window()?.location())?;
+    console::log_1(&wasm_bindgen::JsValue::from_str(&ws_url));
+    //same server address and port as http server
+    WebSocket::new(&ws_url).map_err(|err| Mem2Error::Network(js_error_text(&err)))
+}
+
+///the WebSocket route of the server on the origin of the page.
+///The path and the query like ?room=CODE are not part of it.
+fn ws_url(location: &web_sys::Location) -> Mem2Result<String> {
+    //the origin comes in this format  http://localhost:4000
+    let origin = location
+        .origin()
+        .map_err(|err| Mem2Error::Network(js_error_text(&err)))?;
+    //Only for debugging in the development environment
+    //let origin = String::from("http://192.168.1.57:80");
+    let ws_origin = if let Some(host) = origin.strip_prefix("https://") {
+        format!("wss://{}", host)
+    } else if let Some(host) = origin.strip_prefix("http://") {
+        format!("ws://{}", host)
+    } else {
+        origin
+    };
+    Ok(format!("{}/mem2ws/", ws_origin))
+}
+
+///the same callbacks for the first connection and for every reconnect
+fn setup_ws_handlers(ws: &WebSocket, weak: &dodrio::VdomWeak) {
+    setup_ws_open(ws, weak.clone());
+    setup_ws_msg_recv(ws, weak.clone());
+    setup_ws_close(ws, weak.clone());
+}
+
+///on open send the test and Hello. After a reconnect or a reload rejoin the game in progress.
+fn setup_ws_open(ws: &WebSocket, weak: dodrio::VdomWeak) {
+    //It looks that the first send is in some way a handshake and is part of the connection
+    //it will be execute onopen as a closure
+    let open_handler = Box::new(move || {
+        console::log_1(&"Connection opened, sending 'test' to server".into());
+        wasm_bindgen_futures::spawn_local(
+            weak.with_component({
+                let v2 = weak.clone();
+                move |root| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    game_data.connection_status = ConnectionStatus::Connected;
+                    game_data.send_or_show_error(&WsMessage::ConnectionTest {
+                        test: String::from("test"),
+                    });
+                    send_hello(&mut game_data, false);
+                    //after a reconnect or a reload the game in progress continues with the same other player
+                    if game_data.other_ws_client_instance != 0 {
+                        console::log_1(&"send Rejoin".into());
+                        let ws_message = WsMessage::Rejoin {
+                            ws_client_instance: game_data.my_ws_client_instance,
+                            other_ws_client_instance: game_data.other_ws_client_instance,
+                        };
+                        game_data.send_or_show_error(&ws_message);
+                        resend_all_game_messages(&mut game_data);
+                        //the server can have missed the rematch request while the connection was closed
+                        let is_game_over = matches!(game_data.game_state, GameState::GameOver);
+                        if game_data.is_server_authoritative
+                            && is_game_over
+                            && game_data
+                                .vec_rematch_requests
+                                .contains(&game_data.my_ws_client_instance)
+                        {
+                            send_server_rematch_request(&mut game_data);
+                        }
+                    }
+                    v2.schedule_render();
+                }
+            })
+            .map_err(|_| ()),
+        );
+    });
+
+    let cb_oh: Closure<dyn Fn()> = Closure::wrap(open_handler);
+    ws.set_onopen(Some(cb_oh.as_ref().unchecked_ref()));
+    //don't drop the open_handler memory
+    cb_oh.forget();
+}
+
+///on close try to connect again after a delay that grows with every attempt.
+///After an error the close comes too, so the error is only logged.
+fn setup_ws_close(ws: &WebSocket, weak: dodrio::VdomWeak) {
+    let close_handler = Box::new(move || {
+        console::log_1(&"Connection closed".into());
+        wasm_bindgen_futures::spawn_local(
+            weak.with_component({
+                let v2 = weak.clone();
+                move |root| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    let attempt = match game_data.connection_status {
+                        ConnectionStatus::Reconnecting { attempt } => attempt + 1,
+                        _ => 1,
+                    };
+                    game_data.connection_status = ConnectionStatus::Reconnecting { attempt };
+                    if let Err(error) = schedule_reconnect(v2.clone(), reconnect_delay_ms(attempt))
+                    {
+                        game_data.show_error(error);
+                    }
+                    v2.schedule_render();
+                }
+            })
+            .map_err(|_| ()),
+        );
+    });
+    let cb_ch: Closure<dyn Fn()> = Closure::wrap(close_handler);
+    ws.set_onclose(Some(cb_ch.as_ref().unchecked_ref()));
+    //don't drop the close_handler memory
+    cb_ch.forget();
+
+    let error_handler = Box::new(move || {
+        console::log_1(&"Connection error".into());
+    });
+    let cb_eh: Closure<dyn Fn()> = Closure::wrap(error_handler);
+    ws.set_onerror(Some(cb_eh.as_ref().unchecked_ref()));
+    //don't drop the error_handler memory
+    cb_eh.forget();
+}
+
+///exponential backoff: 0.5s, 1s, 2s, 4s,... but not more than 16s
+fn reconnect_delay_ms(attempt: u32) -> i32 {
+    let mut delay_ms = RECONNECT_MIN_DELAY_MS;
+    for _ in 1..attempt {
+        if delay_ms >= RECONNECT_MAX_DELAY_MS {
+            break;
+        }
+        delay_ms *= 2;
+    }
+    delay_ms.min(RECONNECT_MAX_DELAY_MS)
+}
+
+///after the delay open a new WebSocket and put it in the game data
+fn schedule_reconnect(weak: dodrio::VdomWeak, delay_ms: i32) -> Mem2Result<()> {
+    let reconnect_handler = Box::new(move || {
+        wasm_bindgen_futures::spawn_local(
+            weak.with_component({
+                let v2 = weak.clone();
+                move |root| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    console::log_1(&"reconnect".into());
+                    match setup_ws_connection() {
+                        Ok(ws) => {
+                            setup_ws_handlers(&ws, &v2);
+                            game_data.ws = ws;
+                        }
+                        Err(error) => {
+                            game_data.show_error(error);
+                            if let Err(error) =
+                                schedule_reconnect(v2.clone(), RECONNECT_MAX_DELAY_MS)
+                            {
+                                game_data.show_error(error);
+                            }
+                        }
+                    }
+                }
+            })
+            .map_err(|_| ()),
+        );
+    });
+    let cb_rh: Closure<dyn Fn()> = Closure::wrap(reconnect_handler);
+    browser_window()?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            cb_rh.as_ref().unchecked_ref(),
+            delay_ms,
+        )
+        .map_err(|err| Mem2Error::Browser(js_error_text(&err)))?;
+    //don't drop the reconnect_handler memory
+    cb_rh.forget();
+    Ok(())
+}
+
+///the messages can be sent only over an open connection
+fn is_connected(game_data: &GameData) -> bool {
+    match game_data.connection_status {
+        ConnectionStatus::Connected => true,
+        _ => false,
+    }
+}
+
+///the moves of the server authoritative mode are not sequenced.
+///After a reconnect the Rejoin brings the server state.
+fn send_to_server(game_data: &mut GameData, ws_message: &WsMessage) {
+    if is_connected(game_data) {
+        game_data.send_or_show_error(ws_message);
+    }
+}
+
+///serialize the message and send it over the WebSocket
+fn send_ws_message(ws: &WebSocket, ws_message: &WsMessage) -> Mem2Result<()> {
+    let json =
+        serde_json::to_string(ws_message).map_err(|err| Mem2Error::Network(err.to_string()))?;
+    send_ws_json(ws, &json)
+}
+
+///send the json over the WebSocket
+fn send_ws_json(ws: &WebSocket, json: &str) -> Mem2Result<()> {
+    ws.send_with_str(json)
+        .map_err(|err| Mem2Error::Network(format!("cannot send: {}", js_error_text(&err))))
+}
+
+///after a reconnect the other player can miss any message that is not acknowledged
+fn resend_all_game_messages(game_data: &mut GameData) {
+    let vec_json = game_data.reliable_channel.resend_all(js_sys::Date::now());
+    for json in vec_json {
+        if let Err(error) = send_ws_json(&game_data.ws, &json) {
+            game_data.show_error(error);
+        }
+    }
+}
+
+///send the game message with the next sequence number and remember it for resend
+fn send_game_message(game_data: &mut GameData, make_message: impl FnOnce(u64) -> WsMessage) {
+    //nobody else plays the local game
+    if game_data.is_local_game() {
+        return;
+    }
+    let seq = game_data.reliable_channel.next_seq();
+    let json = match serde_json::to_string(&make_message(seq)) {
+        Ok(json) => json,
+        Err(err) => {
+            game_data.show_error(Mem2Error::Network(err.to_string()));
+            return;
+        }
+    };
+    //without connection the message waits for the resend after the reconnect
+    if is_connected(game_data) {
+        if let Err(error) = send_ws_json(&game_data.ws, &json) {
+            game_data.show_error(error);
+        }
+    }
+    game_data
+        .reliable_channel
+        .sent(seq, json, js_sys::Date::now());
+}
+
+///every second resend the game messages that are not acknowledged
+fn setup_resend_timer(vdom: &dodrio::Vdom) -> Mem2Result<()> {
+    let weak = vdom.weak();
+    let resend_handler = Box::new(move || {
+        wasm_bindgen_futures::spawn_local(
+            weak.with_component({
+                move |root| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    if !is_connected(&game_data) {
+                        return;
+                    }
+                    let vec_json = game_data
+                        .reliable_channel
+                        .due_for_resend(js_sys::Date::now());
+                    for json in vec_json {
+                        console::log_1(&"resend game message".into());
+                        if let Err(error) = send_ws_json(&game_data.ws, &json) {
+                            game_data.show_error(error);
+                        }
+                    }
+                }
+            })
+            .map_err(|_| ()),
+        );
+    });
+    let cb_rh: Closure<dyn Fn()> = Closure::wrap(resend_handler);
+    browser_window()?
+        .set_interval_with_callback_and_timeout_and_arguments_0(
+            cb_rh.as_ref().unchecked_ref(),
+            1000,
+        )
+        .map_err(|err| Mem2Error::Browser(js_error_text(&err)))?;
+    //don't drop the resend_handler memory
+    cb_rh.forget();
+    Ok(())
+}
+
+///in the solo mode the clock runs and the 2 cards that did not match are hidden after a delay.
+///Against the computer it makes its moves.
+fn setup_local_game_timer(vdom: &dodrio::Vdom) -> Mem2Result<()> {
+    let weak = vdom.weak();
+    let local_game_handler = Box::new(move || {
+        wasm_bindgen_futures::spawn_local(
+            weak.with_component({
+                let v2 = weak.clone();
+                move |root| {
+                    let root_rendering_component = root.unwrap_mut::<RootRenderingComponent>();
+                    //this game_data mutable reference is dropped on the end of the function
+                    let mut game_data = root_rendering_component.rc.borrow_mut();
+                    if let GameState::Play = game_data.game_state {
+                        if game_data.computer_player.is_some() {
+                            let vec_events = computer_move(&mut game_data);
+                            if !vec_events.is_empty() {
+                                root_rendering_component
+                                    .react_to_game_events(&mut game_data, &vec_events);
+                                v2.schedule_render();
+                            }
+                        }
+                    }
+                    if !game_data.is_solo || game_data.game_end_ms > 0.0 {
+                        return;
+                    }
+                    if let GameState::Play = game_data.game_state {
+                        let game_engine = &game_data.game_engine;
+                        //after a reload the cards are hidden at once
+                        if game_engine.count_click_inside_one_turn >= 2
+                            && game_engine.is_pair_revealed()
+                            && !game_engine.is_pair_matched()
+                            && js_sys::Date::now() - game_data.solo_unmatched_since_ms
+                                >= SOLO_HIDE_DELAY_MS
+                        {
+                            game_data.game_engine.apply(GameAction::TakeTurn);
+                            save_game_to_session_storage(&mut game_data);
+                        }
+                        //the clock
+                        v2.schedule_render();
+                    }
+                }
+            })
+            .map_err(|_| ()),
+        );
+    });
+    let cb_lgh: Closure<dyn Fn()> = Closure::wrap(local_game_handler);
+    browser_window()?
+        .set_interval_with_callback_and_timeout_and_arguments_0(
+            cb_lgh.as_ref().unchecked_ref(),
+            500,
+        )
+        .map_err(|err| Mem2Error::Browser(js_error_text(&err)))?;
+    //don't drop the local_game_handler memory
+    cb_lgh.forget();
+    Ok(())
+}
+
+///the game messages from the other player are processed in the right order
+fn process_game_message(
+    root_rendering_component: &RootRenderingComponent,
+    game_data: &mut GameData,
+    game_message: WsMessage,
+) {
+    match game_message {
+        WsMessage::PlayerClick {
+            card_index,
+            count_click_inside_one_turn,
+            card_reveal,
+            state_hash,
+            ..
+        } => {
+            console::log_1(&"player_click".into());
+            if let Err(rule_violation) = game_data.game_engine.validate_remote_click(
+                other_player_number(game_data),
+                card_index,
+                count_click_inside_one_turn,
+            ) {
+                reject_move(game_data, rule_violation);
+                return;
+            }
+            let mut vec_events = game_data
+                .game_engine
+                .apply(GameAction::Click { card_index });
+            if let Some(card_reveal) = card_reveal {
+                //the deck owner clicked and revealed the card
+                vec_events.extend(
+                    game_data
+                        .game_engine
+                        .apply(GameAction::Reveal { card_reveal }),
+                );
+            } else if let Some(card_reveal) = game_data.game_engine.reveal(card_index) {
+                //I am the deck owner and I reveal the card the other player clicked
+                let ws_client_instance = game_data.my_ws_client_instance;
+                let state_hash = game_data.game_engine.state_hash();
+                send_game_message(game_data, |seq| WsMessage::CardReveal {
+                    ws_client_instance,
+                    card_reveal,
+                    state_hash,
+                    seq,
+                });
+            }
+            root_rendering_component.react_to_game_events(game_data, &vec_events);
+            check_state_hash(game_data, state_hash);
+        }
+        WsMessage::CardReveal {
+            card_reveal,
+            state_hash,
+            ..
+        } => {
+            console::log_1(&"CardReveal".into());
+            let vec_events = game_data
+                .game_engine
+                .apply(GameAction::Reveal { card_reveal });
+            root_rendering_component.react_to_game_events(game_data, &vec_events);
+            check_state_hash(game_data, state_hash);
+        }
+        WsMessage::PlayerChange { state_hash, .. } => {
+            console::log_1(&"PlayerChange".into());
+            if let Err(rule_violation) = game_data
+                .game_engine
+                .validate_take_turn(other_player_number(game_data))
+            {
+                reject_move(game_data, rule_violation);
+                return;
+            }
+            game_data.game_engine.apply(GameAction::TakeTurn);
+            check_state_hash(game_data, state_hash);
+        }
+        WsMessage::ResyncRequest { .. } => {
+            console::log_1(&"ResyncRequest".into());
+            send_resync_snapshot(game_data);
+        }
+        WsMessage::ResyncSnapshot { game_snapshot, .. } => {
+            console::log_1(&"ResyncSnapshot".into());
+            if game_data.game_engine.snapshot().is_some() {
+                //the deck owner has the true state and never takes the state of the other player
+                console::log_1(&"the deck owner ignores the snapshot".into());
+            } else if !game_data.game_engine.restore_snapshot(&game_snapshot) {
+                console::log_1(&"the snapshot does not fit the deck".into());
+            }
+        }
+        WsMessage::RematchRequest {
+            ws_client_instance,
+            room_code,
+            ..
+        } => {
+            let is_game_over = matches!(game_data.game_state, GameState::GameOver);
+            if room_code != game_data.room_code || !is_game_over {
+                return;
+            }
+            console::log_1(&"RematchRequest".into());
+            if !game_data.vec_rematch_requests.contains(&ws_client_instance) {
+                game_data.vec_rematch_requests.push(ws_client_instance);
+            }
+            if let Err(error) = deal_rematch_if_both_want_it(game_data) {
+                game_data.show_error(error);
+            }
+        }
+        WsMessage::Rematch {
+            room_code,
+            grid_size,
+            vec_card_commitments,
+            starting_player,
+            ..
+        } => {
+            let is_game_over = matches!(game_data.game_state, GameState::GameOver);
+            if room_code != game_data.room_code || !is_game_over {
+                return;
+            }
+            console::log_1(&"Rematch".into());
+            //the cards are hidden until the deck owner reveals them
+            game_data.game_engine = GameEngine::from_commitments(grid_size, vec_card_commitments);
+            game_data.game_engine.vec_pairs = game_data.pack_pairs();
+            game_data.game_engine.start_with_player(starting_player);
+            game_data.game_state = GameState::Play;
+            start_clock(game_data);
+        }
+        _ => {}
+    }
+}
+
+///the names and my player number from the players of the room of the server referee.
+///Some other player of the room is the one to rejoin after a reconnect.
+fn set_room_players(game_data: &mut GameData, vec_room_players: &[RoomPlayer]) {
+    let my_ws_client_instance = game_data.my_ws_client_instance;
+    game_data.vec_player_names = vec_room_players
+        .iter()
+        .map(|room_player| room_player.player_name.clone())
+        .collect();
+    game_data.this_machine_player_number = vec_room_players
+        .iter()
+        .position(|room_player| room_player.ws_client_instance == my_ws_client_instance)
+        .map_or(0, |index| index + 1);
+    if let Some(room_player) = vec_room_players
+        .iter()
+        .find(|room_player| room_player.ws_client_instance != my_ws_client_instance)
     {
-        //this game_data mutable reference is dropped on the end of the function
-        let game_data = self.rc.borrow();
-        //return
-        div(bump)
-            .attr("class", "grid_container_players")
-            .attr(
-                "style",
-                bumpalo::format!(in bump, "grid-template-columns: auto auto auto;{}","")
-                    .into_bump_str(),
-            )
-            .children([
-                div(bump)
-                    .attr("class", "grid_item")
-                    .attr(
-                        "style",
-                        bumpalo::format!(in bump,"text-align: left;color:{};text-decoration:{}",
-                            if game_data.player_turn==1 {"green"} else {"red"},
-                            if game_data.this_machine_player_number==1 {"underline"} else {"none"}
-                        )
-                        .into_bump_str(),
-                    )
-                    .children([text(
-                        bumpalo::format!(in bump, "player1: {}",game_data.player1_points)
-                            .into_bump_str(),
-                    )])
-                    .finish(),
-                div(bump)
-                    .attr("class", "grid_item")
-                    .attr("style", "text-align: center;")
-                    .children([text("")])
-                    .finish(),
-                div(bump)
-                    .attr("class", "grid_item")
-                    .attr(
-                        "style",
-                        bumpalo::format!(in bump,"text-align: right;color:{};text-decoration:{}",
-                            if game_data.player_turn==2 {"green"} else {"red"},
-                            if game_data.this_machine_player_number==2 {"underline"} else {"none"}
-                        )
-                        .into_bump_str(),
-                    )
-                    .children([text(
-                        bumpalo::format!(in bump, "player2: {}",game_data.player2_points)
-                            .into_bump_str(),
-                    )])
-                    .finish(),
-            ])
-            .finish()
+        game_data.other_ws_client_instance = room_player.ws_client_instance;
     }
 }
-//endregion
 
-//region: websocket communication
-///setup websocket connection
-fn setup_ws_connection(location_href: &str) -> WebSocket {
-    //web-sys has websocket for Rust exactly like javascript has¸
-    console::log_1(&"location_href".into());
-    console::log_1(&wasm_bindgen::JsValue::from_str(location_href));
-    //location_href comes in this format  http://localhost:4000/
-    let mut loc_href = location_href
-        .replace("http://", "ws://")
-        .replace("https://", "wss://");
-    //Only for debugging in the development environment
-    //let mut loc_href = String::from("ws://192.168.1.57:80/");
-    loc_href.push_str("mem2ws/");
-    console::log_1(&wasm_bindgen::JsValue::from_str(&loc_href));
-    //same server address and port as http server
-    let ws = WebSocket::new(&loc_href).expect("WebSocket failed to connect.");
+///the player number of the other player
+fn other_player_number(game_data: &GameData) -> usize {
+    if game_data.this_machine_player_number == 1 {
+        2
+    } else {
+        1
+    }
+}
 
-    //I don't know why is clone needed
-    let ws_c = ws.clone();
-    //It looks that the first send is in some way a handshake and is part of the connection
-    //it will be execute onopen as a closure
-    let open_handler = Box::new(move || {
-        console::log_1(&"Connection opened, sending 'test' to server".into());
-        ws_c.send_with_str(
-            &serde_json::to_string(&WsMessage::ConnectionTest {
-                test: String::from("test"),
-            })
-            .expect("error sending test"),
-        )
-        .expect("Failed to send 'test' to server");
-    });
+///the illegal move of the other player is not applied. It is logged and shown as a protocol error.
+fn reject_move(game_data: &mut GameData, rule_violation: RuleViolation) {
+    game_data.show_error(Mem2Error::Protocol(format!(
+        "The move of the other player was rejected: {}.",
+        rule_violation
+    )));
+}
 
-    let cb_oh: Closure<dyn Fn()> = Closure::wrap(open_handler);
-    ws.set_onopen(Some(cb_oh.as_ref().unchecked_ref()));
-    //don't drop the open_handler memory
-    cb_oh.forget();
-    ws
+///compare the state hash of the other player with the local one.
+///On mismatch the deck owner sends the snapshot, the other player asks for it.
+fn check_state_hash(game_data: &mut GameData, other_state_hash: Option<String>) {
+    let state_hash = game_data.game_engine.state_hash();
+    if other_state_hash.is_none() || state_hash.is_none() || other_state_hash == state_hash {
+        return;
+    }
+    console::log_1(&"the game states differ".into());
+    if game_data.game_engine.snapshot().is_some() {
+        send_resync_snapshot(game_data);
+    } else {
+        let ws_client_instance = game_data.my_ws_client_instance;
+        send_game_message(game_data, |seq| WsMessage::ResyncRequest {
+            ws_client_instance,
+            seq,
+        });
+    }
+}
+
+///only the deck owner can send the snapshot of the public game state
+fn send_resync_snapshot(game_data: &mut GameData) {
+    if let Some(game_snapshot) = game_data.game_engine.snapshot() {
+        let ws_client_instance = game_data.my_ws_client_instance;
+        send_game_message(game_data, |seq| WsMessage::ResyncSnapshot {
+            ws_client_instance,
+            game_snapshot,
+            seq,
+        });
+    }
+}
+
+///send Hello with the protocol version and features of this client
+fn send_hello(game_data: &mut GameData, is_reply: bool) {
+    let ws_message = WsMessage::Hello {
+        ws_client_instance: game_data.my_ws_client_instance,
+        protocol_version: PROTOCOL_VERSION,
+        features: PROTOCOL_FEATURES.iter().map(|x| (*x).to_string()).collect(),
+        is_reply,
+    };
+    game_data.send_or_show_error(&ws_message);
+}
+
+///the other client can play with this client only with the same protocol version and all the features.
+///Returns the explanation if it cannot.
+fn check_protocol(protocol_version: usize, features: &[String]) -> Result<(), String> {
+    if protocol_version != PROTOCOL_VERSION {
+        return Err(format!(
+            "The other player uses the protocol version {}, but this client uses the version {}. Both must refresh the webpage to get the same version.",
+            protocol_version, PROTOCOL_VERSION
+        ));
+    }
+    let vec_missing: Vec<&str> = PROTOCOL_FEATURES
+        .iter()
+        .filter(|feature| !features.iter().any(|x| x == *feature))
+        .copied()
+        .collect();
+    if vec_missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The other player does not have the features: {}.",
+            vec_missing.join(", ")
+        ))
+    }
 }
 
 /// receive websocket msg callback. I don't understand this much. Too much future and promises.
-fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
+fn setup_ws_msg_recv(ws: &WebSocket, weak: dodrio::VdomWeak) {
     //Player1 on machine1 have a button Ask player to play! before he starts to play.
     //Click and it sends the WsMessage want_to_play. Player1 waits for the reply and cannot play.
     //Player2 on machine2 see the WsMessage and Accepts it.
@@ -1091,23 +3147,109 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
     //Machine2 receives the WsMessage and runs the same code as the player would click. The cardgrid is blocked.
     //The method with_component() needs a future (promise) It will be executed on the next vdom tick.
     //This is the only way I found to write to CardGrid fields.
-    let weak = vdom.weak();
     let msg_recv_handler = Box::new(move |msg: JsValue| {
-        let data: JsValue =
-            Reflect::get(&msg, &"data".into()).expect("No 'data' field in websocket message!");
+        //only text messages are expected
+        let data = match Reflect::get(&msg, &"data".into())
+            .ok()
+            .and_then(|data| data.as_string())
+        {
+            Some(data) => data,
+            None => {
+                console::log_1(&"the websocket message is not text".into());
+                return;
+            }
+        };
 
         //serde_json can find out the variant of WsMessage
         //parse json and put data in the enum
-        let msg: WsMessage =
-            serde_json::from_str(&data.as_string().expect("Field 'data' is not string"))
-                .unwrap_or_else(|_x| WsMessage::ConnectionTest {
-                    test: String::from("error"),
-                });
+        let msg: WsMessage = match serde_json::from_str(&data) {
+            Ok(msg) => msg,
+            Err(err) => {
+                //unknown or malformed message comes from a client with a different version
+                let explanation = format!(
+                    "Received an unknown message ({}). The other player probably uses a different version of mem2.",
+                    err
+                );
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            game_data.show_error(Mem2Error::Protocol(explanation));
+                            v2.schedule_render();
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+                return;
+            }
+        };
 
         //match enum by variant and prepares the future that will be executed on the next tick
         match msg {
             WsMessage::ConnectionTest { test } => console::log_1(&test.into()),
-            WsMessage::WantToPlay { ws_client_instance } => {
+            //only the server receives the moves and the joins of the server authoritative mode
+            WsMessage::ServerClick { .. }
+            | WsMessage::ServerTakeTurn { .. }
+            | WsMessage::JoinRoom { .. } => {}
+            WsMessage::Hello {
+                ws_client_instance,
+                protocol_version,
+                features,
+                is_reply,
+            } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            //don't listen the echo to yourself
+                            if ws_client_instance != game_data.my_ws_client_instance {
+                                console::log_1(&"rcv Hello".into());
+                                match check_protocol(protocol_version, &features) {
+                                    Ok(()) => {
+                                        if !game_data
+                                            .vec_compatible_ws_client_instances
+                                            .contains(&ws_client_instance)
+                                        {
+                                            game_data
+                                                .vec_compatible_ws_client_instances
+                                                .push(ws_client_instance);
+                                        }
+                                    }
+                                    Err(explanation) => {
+                                        game_data.show_error(Mem2Error::Protocol(explanation));
+                                    }
+                                }
+                                //the new client needs to know about me too
+                                if !is_reply {
+                                    send_hello(&mut game_data, true);
+                                    //and about my open room
+                                    if let GameState::Asking = game_data.game_state {
+                                        send_want_to_play(&mut game_data);
+                                    }
+                                }
+                                v2.schedule_render();
+                            }
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            WsMessage::WantToPlay {
+                ws_client_instance,
+                room_code,
+                grid_size,
+                is_server_authoritative,
+                content_folder_name,
+                player_name,
+            } => {
                 wasm_bindgen_futures::spawn_local(
                     weak.with_component({
                         let v2 = weak.clone();
@@ -1116,11 +3258,55 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
                                 root.unwrap_mut::<RootRenderingComponent>();
                             //this game_data mutable reference is dropped on the end of the function
                             let mut game_data = root_rendering_component.rc.borrow_mut();
+                            //don't listen the echo to yourself
+                            if ws_client_instance == game_data.my_ws_client_instance {
+                                return;
+                            }
                             if let GameState::Start = game_data.game_state {
                                 console::log_1(&"rcv wanttoplay".into());
-                                game_data.game_state = GameState::Asked;
-                                game_data.other_ws_client_instance = ws_client_instance;
-                                v2.schedule_render();
+                                if !game_data
+                                    .vec_compatible_ws_client_instances
+                                    .contains(&ws_client_instance)
+                                {
+                                    //reject the incompatible player with a visible explanation
+                                    game_data.show_error(Mem2Error::Protocol(format!(
+                                        "Player {} wants to play, but did not say Hello with a compatible protocol.",
+                                        ws_client_instance
+                                    )));
+                                    v2.schedule_render();
+                                } else if game_data.room_code.is_empty() {
+                                    //show the room in the lobby. The room announced again replaces the old one.
+                                    game_data
+                                        .vec_open_rooms
+                                        .retain(|open_room| open_room.room_code != room_code);
+                                    game_data.vec_open_rooms.push(OpenRoom {
+                                        room_code,
+                                        ws_client_instance,
+                                        grid_size,
+                                        is_server_authoritative,
+                                        content_folder_name,
+                                        player_name,
+                                    });
+                                    v2.schedule_render();
+                                } else if game_data.room_code == room_code {
+                                    //the link with this room code was opened
+                                    //the content pack and the cards for the grid size of the other player
+                                    if let Err(error) = join_content_pack(
+                                        &mut game_data,
+                                        &v2,
+                                        &content_folder_name,
+                                        grid_size,
+                                    ) {
+                                        game_data.show_error(error);
+                                        v2.schedule_render();
+                                        return;
+                                    }
+                                    game_data.game_state = GameState::Asked;
+                                    game_data.other_ws_client_instance = ws_client_instance;
+                                    game_data.is_server_authoritative = is_server_authoritative;
+                                    game_data.vec_player_names = vec![player_name];
+                                    v2.schedule_render();
+                                }
                             }
                         }
                     })
@@ -1129,7 +3315,11 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
             }
             WsMessage::AcceptPlay {
                 ws_client_instance,
-                card_grid_data,
+                room_code,
+                grid_size,
+                vec_card_commitments,
+                content_folder_name,
+                player_name,
             } => {
                 wasm_bindgen_futures::spawn_local(
                     weak.with_component({
@@ -1140,46 +3330,86 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
                                 root.unwrap_mut::<RootRenderingComponent>();
                             //this game_data mutable reference is dropped on the end of the function
                             let mut game_data = root_rendering_component.rc.borrow_mut();
-
-                            game_data.player_turn = 1;
-                            game_data.game_state = GameState::Play;
-                            let v: Vec<Card> = serde_json::from_str(card_grid_data.as_str())
-                                .expect("Field 'text' is not Vec<Card>");
-                            game_data.vec_cards = v;
-                            game_data.other_ws_client_instance = ws_client_instance;
+                            //the room is full and it is not shown in the lobby anymore
+                            game_data
+                                .vec_open_rooms
+                                .retain(|open_room| open_room.room_code != room_code);
+                            if room_code != game_data.room_code {
+                                //other rooms are not my business
+                            } else if ws_client_instance == game_data.my_ws_client_instance {
+                                //the echo of my AcceptPlay came first. The room is mine.
+                                game_data.is_room_join_pending = false;
+                            } else if game_data.is_room_join_pending {
+                                //another player joined the room before me
+                                if let Err(error) = remove_game_from_session_storage() {
+                                    game_data.show_error(error);
+                                }
+                                game_data.game_state = GameState::Start;
+                                game_data.this_machine_player_number = 0;
+                                game_data.other_ws_client_instance = 0;
+                                game_data.room_code = String::new();
+                                game_data.is_room_join_pending = false;
+                                game_data.is_server_authoritative = false;
+                                game_data.show_error(Mem2Error::Protocol(format!(
+                                    "The room {} was taken by another player.",
+                                    room_code
+                                )));
+                            } else if let GameState::Asking = game_data.game_state {
+                                if !game_data
+                                    .vec_compatible_ws_client_instances
+                                    .contains(&ws_client_instance)
+                                {
+                                    //reject the incompatible player with a visible explanation
+                                    game_data.show_error(Mem2Error::Protocol(format!(
+                                        "Player {} accepted to play, but did not say Hello with a compatible protocol.",
+                                        ws_client_instance
+                                    )));
+                                    v2.schedule_render();
+                                    return;
+                                }
+                                //both players use the content pack of the room
+                                if let Err(error) =
+                                    choose_content_pack(&mut game_data, &v2, &content_folder_name)
+                                {
+                                    game_data.show_error(error);
+                                }
+                                game_data.game_state = GameState::Play;
+                                game_data.vec_player_names =
+                                    vec![game_data.my_player_name.clone(), player_name];
+                                //the cards are hidden until the deck owner reveals them
+                                game_data.game_engine =
+                                    GameEngine::from_commitments(grid_size, vec_card_commitments);
+                                game_data.game_engine.vec_pairs = game_data.pack_pairs();
+                                game_data.game_engine.start();
+                                game_data.reliable_channel = ReliableChannel::new();
+                                game_data.other_ws_client_instance = ws_client_instance;
+                                start_clock(&mut game_data);
+                                save_game_to_session_storage(&mut game_data);
+                            }
                             v2.schedule_render();
-                            //}
                         }
                     })
                     .map_err(|_| ()),
                 );
             }
-            WsMessage::PlayerClick {
+            WsMessage::Rejoin {
                 ws_client_instance,
-                card_index,
-                count_click_inside_one_turn,
+                other_ws_client_instance,
             } => {
                 wasm_bindgen_futures::spawn_local(
                     weak.with_component({
                         let v2 = weak.clone();
-                        console::log_1(&"player_click".into());
                         move |root| {
                             let root_rendering_component =
                                 root.unwrap_mut::<RootRenderingComponent>();
                             //this game_data mutable reference is dropped on the end of the function
                             let mut game_data = root_rendering_component.rc.borrow_mut();
-                            //rcv only from one other player
-                            if ws_client_instance == game_data.other_ws_client_instance {
-                                console::log_1(&"other_ws_client_instance".into());
-                                game_data.count_click_inside_one_turn = count_click_inside_one_turn;
-                                if count_click_inside_one_turn == 1 {
-                                    game_data.card_index_of_first_click = card_index;
-                                } else if count_click_inside_one_turn == 2 {
-                                    game_data.card_index_of_second_click = card_index;
-                                } else {
-                                    //nothing
-                                }
-                                root_rendering_component.card_on_click(game_data);
+                            //only the other player of the game in progress can rejoin
+                            if ws_client_instance == game_data.other_ws_client_instance
+                                && other_ws_client_instance == game_data.my_ws_client_instance
+                            {
+                                console::log_1(&"rcv Rejoin".into());
+                                resend_all_game_messages(&mut game_data);
                                 v2.schedule_render();
                             }
                         }
@@ -1187,7 +3417,146 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
                     .map_err(|_| ()),
                 );
             }
-            WsMessage::PlayerChange { ws_client_instance } => {
+            WsMessage::ServerRematchRequest { ws_client_instance } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            let is_game_over = matches!(game_data.game_state, GameState::GameOver);
+                            //the server deals the fresh deck, here it is only counted
+                            if !game_data.is_server_authoritative || !is_game_over {
+                                return;
+                            }
+                            console::log_1(&"rcv ServerRematchRequest".into());
+                            if !game_data.vec_rematch_requests.contains(&ws_client_instance) {
+                                game_data.vec_rematch_requests.push(ws_client_instance);
+                            }
+                            v2.schedule_render();
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            WsMessage::ServerState {
+                room_code,
+                grid_size,
+                vec_card_commitments,
+                game_snapshot,
+                vec_room_players,
+            } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if !game_data.is_server_authoritative
+                                || room_code != game_data.room_code
+                            {
+                                return;
+                            }
+                            console::log_1(&"rcv ServerState".into());
+                            //the first state of the game or the fresh deck of the rematch
+                            let is_new_deck =
+                                game_data.game_engine.vec_card_commitments != vec_card_commitments;
+                            let mut game_engine =
+                                GameEngine::from_commitments(grid_size, vec_card_commitments);
+                            game_engine.vec_pairs = game_data.pack_pairs();
+                            if !game_engine.restore_snapshot(&game_snapshot) {
+                                console::log_1(
+                                    &"the server state does not match the commitments".into(),
+                                );
+                                return;
+                            }
+                            //the sound plays for the cards that are newly face up
+                            let vec_events: Vec<GameEvent> = game_engine
+                                .vec_cards
+                                .iter()
+                                .filter(|card| {
+                                    card.status != CardStatusCardFace::Down
+                                        && game_data
+                                            .game_engine
+                                            .vec_cards
+                                            .get(card.card_index_and_id)
+                                            .map_or(true, |old_card| {
+                                                old_card.status == CardStatusCardFace::Down
+                                            })
+                                })
+                                .map(|card| GameEvent::CardRevealed {
+                                    card_index: card.card_index_and_id,
+                                })
+                                .collect();
+                            game_data.game_engine = game_engine;
+                            //the host or a player that joined: the game started
+                            game_data.game_state = GameState::Play;
+                            if is_new_deck {
+                                start_clock(&mut game_data);
+                            }
+                            set_room_players(&mut game_data, &vec_room_players);
+                            root_rendering_component
+                                .react_to_game_events(&mut game_data, &vec_events);
+                            save_game_to_session_storage(&mut game_data);
+                            v2.schedule_render();
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            WsMessage::RoomPlayers {
+                room_code,
+                vec_room_players,
+            } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if room_code != game_data.room_code {
+                                return;
+                            }
+                            console::log_1(&"rcv RoomPlayers".into());
+                            match game_data.game_state {
+                                GameState::Asking => {
+                                    set_room_players(&mut game_data, &vec_room_players);
+                                }
+                                GameState::Joined => {
+                                    let my_ws_client_instance = game_data.my_ws_client_instance;
+                                    if vec_room_players.iter().any(|room_player| {
+                                        room_player.ws_client_instance == my_ws_client_instance
+                                    }) {
+                                        set_room_players(&mut game_data, &vec_room_players);
+                                    } else if vec_room_players.len() >= MAX_PLAYERS {
+                                        game_data.game_state = GameState::Start;
+                                        game_data.room_code = String::new();
+                                        game_data.is_server_authoritative = false;
+                                        game_data.vec_player_names = Vec::new();
+                                        game_data.show_error(Mem2Error::Protocol(format!(
+                                            "The room {} is full.",
+                                            room_code
+                                        )));
+                                    }
+                                }
+                                _ => {}
+                            }
+                            v2.schedule_render();
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            WsMessage::StartGame {
+                ws_client_instance,
+                room_code,
+            } => {
                 wasm_bindgen_futures::spawn_local(
                     weak.with_component({
                         let v2 = weak.clone();
@@ -1195,13 +3564,80 @@ fn setup_ws_msg_recv(ws: &WebSocket, vdom: &dodrio::Vdom) {
                             let root_rendering_component =
                                 root.unwrap_mut::<RootRenderingComponent>();
                             //this game_data mutable reference is dropped on the end of the function
-                            //clippy is wrong about dropping the mut. I need it.
-                            let game_data = root_rendering_component.rc.borrow_mut();
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            //the host started the game and the room is not shown in the lobby anymore
+                            game_data.vec_open_rooms.retain(|open_room| {
+                                open_room.room_code != room_code
+                                    || open_room.ws_client_instance != ws_client_instance
+                            });
+                            v2.schedule_render();
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            WsMessage::Ack {
+                ws_client_instance,
+                seq,
+            } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
                             //rcv only from other player
                             if ws_client_instance == game_data.other_ws_client_instance {
-                                console::log_1(&"PlayerChange".into());
-                                root_rendering_component.take_turn(game_data);
-                                v2.schedule_render();
+                                game_data.reliable_channel.ack(seq);
+                            }
+                        }
+                    })
+                    .map_err(|_| ()),
+                );
+            }
+            game_message @ WsMessage::PlayerClick { .. }
+            | game_message @ WsMessage::CardReveal { .. }
+            | game_message @ WsMessage::PlayerChange { .. }
+            | game_message @ WsMessage::ResyncRequest { .. }
+            | game_message @ WsMessage::ResyncSnapshot { .. }
+            | game_message @ WsMessage::RematchRequest { .. }
+            | game_message @ WsMessage::Rematch { .. } => {
+                wasm_bindgen_futures::spawn_local(
+                    weak.with_component({
+                        let v2 = weak.clone();
+                        move |root| {
+                            let root_rendering_component =
+                                root.unwrap_mut::<RootRenderingComponent>();
+                            //this game_data mutable reference is dropped on the end of the function
+                            let mut game_data = root_rendering_component.rc.borrow_mut();
+                            if let Some((ws_client_instance, seq)) = game_message.sequence() {
+                                //rcv only from other player
+                                if ws_client_instance == game_data.other_ws_client_instance {
+                                    //the game gets the messages only in the right order.
+                                    //A message after the receive window is resent later.
+                                    let vec_ready =
+                                        match game_data.reliable_channel.receive(seq, game_message)
+                                        {
+                                            Some(vec_ready) => vec_ready,
+                                            None => return,
+                                        };
+                                    //acknowledge every message, also the duplicates
+                                    let ws_message = WsMessage::Ack {
+                                        ws_client_instance: game_data.my_ws_client_instance,
+                                        seq,
+                                    };
+                                    game_data.send_or_show_error(&ws_message);
+                                    for game_message in vec_ready {
+                                        process_game_message(
+                                            root_rendering_component,
+                                            &mut game_data,
+                                            game_message,
+                                        );
+                                    }
+                                    save_game_to_session_storage(&mut game_data);
+                                    v2.schedule_render();
+                                }
                             }
                         }
                     })
@@ -0,0 +1,47 @@
+//! The errors of the mem2 client.
+//! They are propagated to the game data and shown in the error panel instead of a panic.
+//! The player can dismiss them with a click.
+
+//region: use statements
+use std::fmt;
+use wasm_bindgen::JsValue;
+//endregion
+
+//region: enum, structs, const,...
+///what went wrong
+#[derive(Clone, PartialEq, Debug)]
+pub enum Mem2Error {
+    ///the WebSocket cannot connect or send
+    Network(String),
+    ///the session storage cannot be read or written
+    Storage(String),
+    ///a file from the content folder cannot be loaded or used
+    Content(String),
+    ///the other client does not speak the same protocol or breaks the rules
+    Protocol(String),
+    ///the browser has no window or refuses the location or the timers
+    Browser(String),
+}
+
+///result with the mem2 error
+pub type Mem2Result<T> = Result<T, Mem2Error>;
+//endregion
+
+impl fmt::Display for Mem2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mem2Error::Network(explanation) => write!(f, "Network error: {}", explanation),
+            Mem2Error::Storage(explanation) => write!(f, "Storage error: {}", explanation),
+            Mem2Error::Content(explanation) => write!(f, "Content error: {}", explanation),
+            Mem2Error::Protocol(explanation) => write!(f, "{}", explanation),
+            Mem2Error::Browser(explanation) => write!(f, "Browser error: {}", explanation),
+        }
+    }
+}
+
+///the text of a JavaScript error
+pub fn js_error_text(js_value: &JsValue) -> String {
+    js_value
+        .as_string()
+        .unwrap_or_else(|| format!("{:?}", js_value))
+}